@@ -2,9 +2,19 @@ use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response};
 
 use crate::error::ContractResult;
 use crate::msg::{ExecuteMsg, QueryMsg};
-use crate::state::{AUTHOR, PROJECT_COUNT};
+use crate::state::{
+    Config, Ownership, CONFIG, OWNERSHIP, PATRON_COUNT, PROJECT_COUNT, ROUND_COUNT,
+};
 
 pub const THRESHOLD: u128 = 10_000;
+/// The maximum length of a donation memo in bytes.
+pub const MAX_MEMO_LEN: usize = 256;
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const MAX_TAGS: usize = 5;
+/// The maximum length of a project tag in bytes.
+pub const MAX_TAG_LEN: usize = 32;
+/// The maximum number of projects in a round, which keeps finalizing it within the gas limit.
+pub const MAX_ROUND_PROJECTS: usize = 50;
 
 pub fn execute(
     deps: DepsMut,
@@ -23,7 +33,15 @@ pub fn instantiate(
     _msg: Empty,
 ) -> ContractResult<Response> {
     PROJECT_COUNT.save(deps.storage, &0u128)?;
-    AUTHOR.save(deps.storage, &info.sender)?;
+    ROUND_COUNT.save(deps.storage, &0u128)?;
+    PATRON_COUNT.save(deps.storage, &0u128)?;
+    let ownership = Ownership {
+        owner: Some(info.sender.clone()),
+        pending_owner: None,
+        pending_expiry: None,
+    };
+    OWNERSHIP.save(deps.storage, &ownership)?;
+    CONFIG.save(deps.storage, &Config::new(info.sender))?;
     Ok(Response::new())
 }
 
@@ -34,14 +52,25 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
 
 #[cfg(test)]
 mod tests {
-    use super::{execute, instantiate, query};
+    use super::{
+        execute, instantiate, query, MAX_MEMO_LEN, MAX_ROUND_PROJECTS, MAX_TAGS, SECONDS_PER_DAY,
+    };
 
-    use cosmwasm_std::{coins, Addr, Coin, Empty, StdResult};
+    use cosmwasm_std::{coins, Addr, Coin, Decimal, Empty, Event, StdResult, Timestamp, Uint128};
     use cw_multi_test::{App, ContractWrapper, Executor};
+    use cw_utils::Expiration;
 
     use crate::{
-        msg::{ExecuteMsg, ListDonationsForProjectByPatronResp, ListProjectsResp, QueryMsg},
-        state::{DonationTx, Project},
+        error::{ContractError, NonexistentProjectIdError},
+        msg::{
+            CoinSplit, ConfigUpdate, DonationHistoryResp, ExecuteMsg, FeeTier, GetProjectsResp,
+            GetRoundResp, Granularity, HistoryBucket, ListDonationsForProjectByPatronResp,
+            ListFrozenProjectsResp, ListProjectsByCreatorResp, ListProjectsResp, OwnershipTransfer,
+            Payout, PendingOwnershipTransfersResp, ProjectManagersResp, ProjectResp,
+            ProjectTotalsResp, QueryMsg, ReferralEarningsResp, SimulateDonationResp, StatsResp,
+            TopDonorsResp, TopProjectsResp,
+        },
+        state::{Config, DenomStats, DonationTx, Ownership, Project, ProjectRole, ProjectStatus},
     };
 
     #[test]
@@ -64,7 +93,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(addr, &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                addr,
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert!(projects.is_empty());
@@ -90,7 +129,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(&addr, &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                &addr,
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert!(projects.is_empty());
@@ -100,6 +149,7 @@ mod tests {
             addr.clone(),
             &ExecuteMsg::CreateProject {
                 name: "Project".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -107,7 +157,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(&addr, &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                &addr,
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 1);
@@ -147,6 +207,7 @@ mod tests {
             contract.clone(),
             &ExecuteMsg::CreateProject {
                 name: "projectname".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -154,7 +215,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(contract.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                contract.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 1);
@@ -162,7 +233,14 @@ mod tests {
         app.execute_contract(
             patron.clone(),
             contract.clone(),
-            &ExecuteMsg::Donate { project_id: 0 },
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
             &coins(5, "eth"),
         )
         .unwrap();
@@ -190,7 +268,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(donations.len(), 1);
-        assert_eq!(donations[0], DonationTx(coins(5, "eth")));
+        assert_eq!(donations[0], DonationTx::new(coins(5, "eth"), None));
     }
 
     #[test]
@@ -227,6 +305,7 @@ mod tests {
             contract.clone(),
             &ExecuteMsg::CreateProject {
                 name: "projectname".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -234,7 +313,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(contract.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                contract.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 1);
@@ -242,7 +331,14 @@ mod tests {
         app.execute_contract(
             patron.clone(),
             contract.clone(),
-            &ExecuteMsg::Donate { project_id: 0 },
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
             &coins(10, "eth"),
         )
         .unwrap();
@@ -270,7 +366,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(donations.len(), 1);
-        assert_eq!(donations[0], DonationTx(coins(10, "eth")));
+        assert_eq!(donations[0], DonationTx::new(coins(10, "eth"), None));
     }
 
     #[test]
@@ -307,6 +403,7 @@ mod tests {
             contract.clone(),
             &ExecuteMsg::CreateProject {
                 name: "projectname".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -314,7 +411,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(contract.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                contract.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 1);
@@ -322,7 +429,14 @@ mod tests {
         app.execute_contract(
             patron.clone(),
             contract.clone(),
-            &ExecuteMsg::Donate { project_id: 0 },
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
             &coins(10_001, "eth"),
         )
         .unwrap();
@@ -350,7 +464,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(donations.len(), 1);
-        assert_eq!(donations[0], DonationTx(coins(10_001, "eth")));
+        assert_eq!(donations[0], DonationTx::new(coins(10_001, "eth"), None));
     }
 
     #[test]
@@ -387,6 +501,7 @@ mod tests {
             contract.clone(),
             &ExecuteMsg::CreateProject {
                 name: "projectname".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -394,7 +509,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(contract.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                contract.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 1);
@@ -402,7 +527,14 @@ mod tests {
         app.execute_contract(
             patron.clone(),
             contract.clone(),
-            &ExecuteMsg::Donate { project_id: 0 },
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
             &coins(10_020, "eth"),
         )
         .unwrap();
@@ -430,7 +562,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(donations.len(), 1);
-        assert_eq!(donations[0], DonationTx(coins(10_020, "eth")));
+        assert_eq!(donations[0], DonationTx::new(coins(10_020, "eth"), None));
     }
 
     #[test]
@@ -453,7 +585,17 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(addr.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert!(projects.is_empty());
@@ -463,6 +605,7 @@ mod tests {
             addr.clone(),
             &ExecuteMsg::CreateProject {
                 name: "Project0".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -470,11 +613,24 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(addr.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 1);
-        let Project { name, creator } = projects.get(0).unwrap();
+        let ProjectResp {
+            project: Project { name, creator, .. },
+            ..
+        } = projects.first().unwrap();
         assert_eq!(name, "Project0");
         assert_eq!(creator, Addr::unchecked("proj_creator"));
 
@@ -483,6 +639,7 @@ mod tests {
             addr.clone(),
             &ExecuteMsg::CreateProject {
                 name: "Project1".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -490,11 +647,24 @@ mod tests {
 
         let ListProjectsResp { projects } = app
             .wrap()
-            .query_wasm_smart(addr.clone(), &QueryMsg::ListProjects {})
+            .query_wasm_smart(
+                addr.clone(),
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap();
 
         assert_eq!(projects.len(), 2);
-        let Project { name, creator } = projects.get(1).unwrap();
+        let ProjectResp {
+            project: Project { name, creator, .. },
+            ..
+        } = projects.get(1).unwrap();
         assert_eq!(name, "Project1");
         assert_eq!(creator, Addr::unchecked("proj_creator"));
     }
@@ -543,6 +713,7 @@ mod tests {
             contract.clone(),
             &ExecuteMsg::CreateProject {
                 name: "Project0".to_string(),
+                tags: vec![],
             },
             &[],
         )
@@ -564,7 +735,14 @@ mod tests {
         app.execute_contract(
             patron.clone(),
             contract.clone(),
-            &ExecuteMsg::Donate { project_id: 0 },
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
             &coins(10, "eth"),
         )
         .unwrap();
@@ -581,7 +759,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(donations.len(), 1);
-        assert_eq!(donations[0], DonationTx(coins(10, "eth")));
+        assert_eq!(donations[0], DonationTx::new(coins(10, "eth"), None));
 
         let ListDonationsForProjectByPatronResp { donations } = app
             .wrap()
@@ -596,4 +774,3923 @@ mod tests {
 
         assert!(donations.is_empty());
     }
+
+    #[test]
+    fn test_quadratic_funding_round() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let operator = app.api().addr_make("operator");
+        let proj_owner_a = app.api().addr_make("proj_owner_a");
+        let proj_owner_b = app.api().addr_make("proj_owner_b");
+        let patrons: Vec<Addr> = (0..4)
+            .map(|i| app.api().addr_make(&format!("patron{i}")))
+            .collect();
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &operator, coins(100, "eth"))
+                .unwrap();
+            for patron in &patrons {
+                router
+                    .bank
+                    .init_balance(storage, patron, coins(100, "eth"))
+                    .unwrap();
+            }
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for (proj_owner, name) in [(&proj_owner_a, "Project A"), (&proj_owner_b, "Project B")] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let now = app.block_info().time;
+        app.execute_contract(
+            operator.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateRound {
+                name: "Round".to_string(),
+                start: now,
+                end: now.plus_seconds(100),
+            },
+            &coins(100, "eth"),
+        )
+        .unwrap();
+
+        // Only the operator can register projects.
+        let err = app
+            .execute_contract(
+                proj_owner_a.clone(),
+                contract.clone(),
+                &ExecuteMsg::RegisterRoundProject {
+                    round_id: 0,
+                    project_id: 0,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        // Projects only take part in the rounds they accepted.
+        let err = app
+            .execute_contract(
+                proj_owner_a.clone(),
+                contract.clone(),
+                &ExecuteMsg::JoinRound {
+                    round_id: 0,
+                    project_id: 0,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NotInvitedToRound(0, 0)
+        );
+
+        for (project_id, proj_owner) in [(0, &proj_owner_a), (1, &proj_owner_b)] {
+            app.execute_contract(
+                operator.clone(),
+                contract.clone(),
+                &ExecuteMsg::RegisterRoundProject {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::JoinRound {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let GetRoundResp { round, project_ids } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetRound { round_id: 0 })
+            .unwrap();
+        assert_eq!(round.matching_pool.u128(), 100);
+        assert_eq!(project_ids, vec![0, 1]);
+
+        // Project A: (sqrt(4) + sqrt(4))^2 - 8 = 8. Project B: (sqrt(4) + sqrt(16))^2 - 20 = 16.
+        for (patron, project_id, amount) in [
+            (&patrons[0], 0, 4),
+            (&patrons[1], 0, 4),
+            (&patrons[2], 1, 4),
+            (&patrons[3], 1, 16),
+        ] {
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(amount, "eth"),
+            )
+            .unwrap();
+        }
+
+        let err = app
+            .execute_contract(
+                patrons[0].clone(),
+                contract.clone(),
+                &ExecuteMsg::FinalizeRound { round_id: 0 },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::RoundNotEnded(0)
+        );
+
+        app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+        // Donations after the round window are not matched.
+        app.execute_contract(
+            patrons[2].clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 1,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(50, "eth"),
+        )
+        .unwrap();
+
+        app.execute_contract(
+            patrons[0].clone(),
+            contract.clone(),
+            &ExecuteMsg::FinalizeRound { round_id: 0 },
+            &[],
+        )
+        .unwrap();
+
+        // 100 * 8 / 24 = 33 and 100 * 16 / 24 = 66, the remaining 1eth goes back to the operator.
+        let balance = app.wrap().query_balance(&proj_owner_a, "eth").unwrap();
+        assert_eq!(balance, Coin::new(3u128 + 3 + 33, "eth"));
+        let balance = app.wrap().query_balance(&proj_owner_b, "eth").unwrap();
+        assert_eq!(balance, Coin::new(3u128 + 14 + 45 + 66, "eth"));
+        let balance = app.wrap().query_balance(&operator, "eth").unwrap();
+        assert_eq!(balance, Coin::new(1u128, "eth"));
+        let balance = app.wrap().query_balance(&contract, "eth").unwrap();
+        assert_eq!(balance, Coin::new(0u128, "eth"));
+    }
+
+    #[test]
+    fn test_round_project_limit() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let operator = app.api().addr_make("operator");
+        let proj_owner = app.api().addr_make("proj_owner");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &operator, coins(100, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        let now = app.block_info().time;
+        app.execute_contract(
+            operator.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateRound {
+                name: "Round".to_string(),
+                start: now,
+                end: now.plus_seconds(100),
+            },
+            &coins(100, "eth"),
+        )
+        .unwrap();
+
+        for project_id in 0..=MAX_ROUND_PROJECTS as u128 {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: format!("Project {project_id}"),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                operator.clone(),
+                contract.clone(),
+                &ExecuteMsg::RegisterRoundProject {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+            let res = app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::JoinRound {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            );
+            if project_id < MAX_ROUND_PROJECTS as u128 {
+                res.unwrap();
+            } else {
+                assert_eq!(
+                    res.unwrap_err().downcast::<ContractError>().unwrap(),
+                    ContractError::TooManyRoundProjects(MAX_ROUND_PROJECTS)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_donate_many() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner_a = app.api().addr_make("proj_owner_a");
+        let proj_owner_b = app.api().addr_make("proj_owner_b");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(20, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for (proj_owner, name) in [(&proj_owner_a, "Project A"), (&proj_owner_b, "Project B")] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::DonateMany {
+                    allocations: vec![(0, coins(4, "eth")), (1, coins(5, "eth"))],
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::AllocationsMismatch
+        );
+
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::DonateMany {
+                    allocations: vec![(0, coins(4, "eth")), (1, coins(6, "eth"))],
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap();
+
+        // One transfer per recipient: both creators and the fee collector.
+        let transfers = resp.events.iter().filter(|e| e.ty == "transfer").count();
+        assert_eq!(transfers, 3);
+
+        // 4 * 9 / 10 = 3 and 6 * 9 / 10 = 5, so the fees are 1eth each.
+        let balance = app.wrap().query_balance(&proj_owner_a, "eth").unwrap();
+        assert_eq!(balance, Coin::new(3u128, "eth"));
+        let balance = app.wrap().query_balance(&proj_owner_b, "eth").unwrap();
+        assert_eq!(balance, Coin::new(5u128, "eth"));
+        let balance = app.wrap().query_balance(&contract_owner, "eth").unwrap();
+        assert_eq!(balance, Coin::new(2u128, "eth"));
+        let balance = app.wrap().query_balance(&contract, "eth").unwrap();
+        assert_eq!(balance, Coin::new(0u128, "eth"));
+
+        let ListDonationsForProjectByPatronResp { donations } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListDonationsForProjectByPatron {
+                    project_id: 1,
+                    patron: patron.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(donations, vec![DonationTx::new(coins(6, "eth"), None)]);
+
+        // An allocation without funds, or a donation without funds, has no fee rate to report.
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::DonateMany {
+                    allocations: vec![(0, coins(10, "eth")), (1, vec![])],
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap();
+        let has_fee_rate = |event: &Event| event.attributes.iter().any(|a| a.key == "fee_rate");
+        let events: Vec<_> = resp
+            .events
+            .iter()
+            .filter(|e| e.ty == "wasm-donation")
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert!(has_fee_rate(events[0]));
+        assert!(!has_fee_rate(events[1]));
+
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 1,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &[],
+            )
+            .unwrap();
+        let event = resp
+            .events
+            .iter()
+            .find(|e| e.ty == "wasm-donation")
+            .unwrap();
+        assert!(!has_fee_rate(event));
+    }
+
+    #[test]
+    fn test_donate_on_behalf_of() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let gateway = app.api().addr_make("gateway");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &gateway, coins(10, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "projectname".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let resp = app
+            .execute_contract(
+                gateway.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: Some(patron.to_string()),
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap();
+
+        let event = resp
+            .events
+            .iter()
+            .find(|e| e.ty == "wasm-donation")
+            .unwrap();
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+        };
+        assert_eq!(attr("sender"), Some(gateway.to_string()));
+        assert_eq!(attr("patron"), Some(patron.to_string()));
+
+        let ListDonationsForProjectByPatronResp { donations } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListDonationsForProjectByPatron {
+                    project_id: 0,
+                    patron: patron.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(donations, vec![DonationTx::new(coins(10, "eth"), None)]);
+
+        let ListDonationsForProjectByPatronResp { donations } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListDonationsForProjectByPatron {
+                    project_id: 0,
+                    patron: gateway.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(donations.is_empty());
+
+        let balance = app.wrap().query_balance(&proj_owner, "eth").unwrap();
+        assert_eq!(balance, Coin::new(9u128, "eth"));
+    }
+
+    #[test]
+    fn test_donate_anonymously() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(30, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "projectname".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: true,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap();
+        let event = resp
+            .events
+            .iter()
+            .find(|e| e.ty == "wasm-donation")
+            .unwrap();
+        assert!(event.attributes.iter().all(|a| a.key != "patron"));
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(20, "eth"),
+        )
+        .unwrap();
+
+        // The fees are collected and the creator is paid for both donations.
+        let balance = app.wrap().query_balance(&proj_owner, "eth").unwrap();
+        assert_eq!(balance, Coin::new(9u128 + 18, "eth"));
+        let balance = app.wrap().query_balance(&contract_owner, "eth").unwrap();
+        assert_eq!(balance, Coin::new(1u128 + 2, "eth"));
+
+        let ListDonationsForProjectByPatronResp { donations } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListDonationsForProjectByPatron {
+                    project_id: 0,
+                    patron: patron.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(donations, vec![DonationTx::new(coins(20, "eth"), None)]);
+
+        let ProjectTotalsResp { totals } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::ProjectTotals { project_id: 0 })
+            .unwrap();
+        assert_eq!(totals, coins(30, "eth"));
+    }
+
+    #[test]
+    fn test_donate_with_memo() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(20, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "projectname".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: Some("a".repeat(MAX_MEMO_LEN + 1)),
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::MemoTooLong(MAX_MEMO_LEN)
+        );
+
+        for memo in ["", "  "] {
+            let err = app
+                .execute_contract(
+                    patron.clone(),
+                    contract.clone(),
+                    &ExecuteMsg::Donate {
+                        project_id: 0,
+                        on_behalf_of: None,
+                        anonymous: false,
+                        memo: Some(memo.to_string()),
+                        referrer: None,
+                        cover_fee: None,
+                    },
+                    &coins(10, "eth"),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::EmptyMemo
+            );
+        }
+
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: Some("Keep it up!".to_string()),
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap();
+        let event = resp
+            .events
+            .iter()
+            .find(|e| e.ty == "wasm-donation")
+            .unwrap();
+        assert!(event
+            .attributes
+            .iter()
+            .any(|a| a.key == "memo" && a.value == "Keep it up!"));
+
+        let ListDonationsForProjectByPatronResp { donations } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListDonationsForProjectByPatron {
+                    project_id: 0,
+                    patron: patron.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            donations,
+            vec![DonationTx::new(
+                coins(10, "eth"),
+                Some("Keep it up!".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_top_donors() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patrons: Vec<Addr> = (0..3)
+            .map(|i| app.api().addr_make(&format!("patron{i}")))
+            .collect();
+
+        app.init_modules(|router, _, storage| {
+            for patron in &patrons {
+                router
+                    .bank
+                    .init_balance(storage, patron, coins(100, "eth"))
+                    .unwrap();
+            }
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "projectname".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // patron0 overtakes patron1 with the second donation.
+        for (patron, amount) in [
+            (&patrons[0], 20),
+            (&patrons[1], 30),
+            (&patrons[2], 10),
+            (&patrons[0], 20),
+        ] {
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(amount, "eth"),
+            )
+            .unwrap();
+        }
+
+        let TopDonorsResp { donors } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::TopDonors {
+                    project_id: 0,
+                    denom: "eth".to_string(),
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+
+        let donors: Vec<(Addr, u128)> = donors
+            .into_iter()
+            .map(|donor| (donor.patron, donor.amount.u128()))
+            .collect();
+        assert_eq!(
+            donors,
+            vec![(patrons[0].clone(), 40), (patrons[1].clone(), 30)]
+        );
+    }
+
+    #[test]
+    fn test_top_projects() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(100, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for (project_id, amount) in [(0, 10), (1, 30), (2, 20)] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: format!("Project{project_id}"),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(amount, "eth"),
+            )
+            .unwrap();
+        }
+
+        let TopProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::TopProjects {
+                    denom: "eth".to_string(),
+                    include_frozen: false,
+                    limit: Some(2),
+                    start_after: None,
+                },
+            )
+            .unwrap();
+        let projects: Vec<(u128, u128)> = projects
+            .into_iter()
+            .map(|p| (p.project_id, p.amount.u128()))
+            .collect();
+        assert_eq!(projects, vec![(1, 30), (2, 20)]);
+
+        let TopProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::TopProjects {
+                    denom: "eth".to_string(),
+                    include_frozen: false,
+                    limit: Some(2),
+                    start_after: Some(2),
+                },
+            )
+            .unwrap();
+        let projects: Vec<(u128, u128)> = projects
+            .into_iter()
+            .map(|p| (p.project_id, p.amount.u128()))
+            .collect();
+        assert_eq!(projects, vec![(0, 10)]);
+    }
+
+    #[test]
+    fn test_donation_history() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(100, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "projectname".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let first_day = app.block_info().time.seconds() / SECONDS_PER_DAY;
+        let day_start = |day: u64| Timestamp::from_seconds(day * SECONDS_PER_DAY);
+        let week_start = |day: u64| day_start(day / 7 * 7);
+
+        for (day, amount) in [(0, 10), (0, 5), (1, 20), (7, 7)] {
+            app.update_block(|block| {
+                block.time = day_start(first_day + day).plus_seconds(SECONDS_PER_DAY / 2)
+            });
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: true,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(amount, "eth"),
+            )
+            .unwrap();
+        }
+
+        let DonationHistoryResp { buckets } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::DonationHistory {
+                    project_id: 0,
+                    denom: "eth".to_string(),
+                    from: day_start(first_day),
+                    to: day_start(first_day + 1),
+                    granularity: Granularity::Day,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            buckets,
+            vec![
+                HistoryBucket {
+                    start: day_start(first_day),
+                    amount: Uint128::new(15),
+                },
+                HistoryBucket {
+                    start: day_start(first_day + 1),
+                    amount: Uint128::new(20),
+                },
+            ]
+        );
+
+        let DonationHistoryResp { buckets } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::DonationHistory {
+                    project_id: 0,
+                    denom: "eth".to_string(),
+                    from: day_start(first_day),
+                    to: day_start(first_day + 7),
+                    granularity: Granularity::Week,
+                },
+            )
+            .unwrap();
+        // The donations of the first two days may fall into different weeks.
+        let mut expected: Vec<HistoryBucket> = vec![];
+        for (day, amount) in [(0, 15u128), (1, 20), (7, 7)] {
+            let start = week_start(first_day + day);
+            match expected.last_mut() {
+                Some(bucket) if bucket.start == start => bucket.amount += Uint128::new(amount),
+                _ => expected.push(HistoryBucket {
+                    start,
+                    amount: Uint128::new(amount),
+                }),
+            }
+        }
+        assert_eq!(buckets, expected);
+    }
+
+    #[test]
+    fn test_simulate_donation() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        let funds = vec![Coin::new(5u128, "btc"), Coin::new(10_001u128, "eth")];
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, funds.clone())
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "projectname".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let SimulateDonationResp {
+            coins,
+            payouts,
+            refund,
+        } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: funds.clone(),
+                    sender: None,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    referrer: None,
+                    cover_fee: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            coins,
+            vec![
+                CoinSplit {
+                    gross: Coin::new(5u128, "btc"),
+                    net: Coin::new(4u128, "btc"),
+                    fee: Coin::new(1u128, "btc"),
+                    tier: FeeTier::BelowThreshold,
+                    referral: None,
+                },
+                CoinSplit {
+                    gross: Coin::new(10_001u128, "eth"),
+                    net: Coin::new(9_500u128, "eth"),
+                    fee: Coin::new(501u128, "eth"),
+                    tier: FeeTier::AboveThreshold,
+                    referral: None,
+                },
+            ]
+        );
+        assert_eq!(
+            payouts,
+            vec![
+                Payout {
+                    recipient: proj_owner.clone(),
+                    amount: vec![Coin::new(4u128, "btc"), Coin::new(9_500u128, "eth")],
+                },
+                Payout {
+                    recipient: contract_owner.clone(),
+                    amount: vec![Coin::new(1u128, "btc"), Coin::new(501u128, "eth")],
+                },
+            ]
+        );
+        assert!(refund.is_empty());
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &funds,
+        )
+        .unwrap();
+
+        for Payout { recipient, amount } in payouts {
+            for coin in amount {
+                let balance = app.wrap().query_balance(&recipient, &coin.denom).unwrap();
+                assert_eq!(balance, coin);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patrons: Vec<Addr> = (0..2)
+            .map(|i| app.api().addr_make(&format!("patron{i}")))
+            .collect();
+
+        app.init_modules(|router, _, storage| {
+            for patron in &patrons {
+                router
+                    .bank
+                    .init_balance(storage, patron, coins(20_000, "eth"))
+                    .unwrap();
+            }
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for name in ["Project0", "Project1"] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        for (patron, project_id, amount) in [
+            (&patrons[0], 0, 10),
+            (&patrons[0], 1, 20),
+            (&patrons[1], 1, 10_020),
+        ] {
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(amount, "eth"),
+            )
+            .unwrap();
+        }
+
+        let StatsResp {
+            project_count,
+            patron_count,
+            denoms,
+        } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::Stats {})
+            .unwrap();
+        assert_eq!(project_count, 2);
+        assert_eq!(patron_count, 2);
+        assert_eq!(
+            denoms,
+            vec![DenomStats {
+                denom: "eth".to_string(),
+                gross: Uint128::new(10 + 20 + 10_020),
+                fees: Uint128::new(1 + 2 + 501),
+                net: Uint128::new(9 + 18 + 9_519),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_get_project() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(10, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for name in ["Project0", "Project1"] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 1,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(10, "eth"),
+        )
+        .unwrap();
+
+        let ProjectResp {
+            id,
+            project,
+            totals,
+            ..
+        } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 1 })
+            .unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(project.name, "Project1");
+        assert_eq!(project.creator, proj_owner);
+        assert_eq!(project.status, ProjectStatus::Active);
+        assert_eq!(totals, coins(10, "eth"));
+
+        let GetProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProjects { ids: vec![1, 0] })
+            .unwrap();
+        let ids: Vec<u128> = projects.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![1, 0]);
+        assert!(projects[1].totals.is_empty());
+
+        let err = app
+            .wrap()
+            .query_wasm_smart::<GetProjectsResp>(
+                &contract,
+                &QueryMsg::GetProjects { ids: vec![0, 2] },
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&NonexistentProjectIdError(2).to_string()));
+    }
+
+    #[test]
+    fn test_list_projects_by_creator() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let alice = app.api().addr_make("alice");
+        let bob = app.api().addr_make("bob");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for (i, creator) in [&alice, &bob, &alice, &alice].into_iter().enumerate() {
+            app.execute_contract(
+                creator.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: format!("Project{i}"),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let ListProjectsByCreatorResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjectsByCreator {
+                    creator: alice.to_string(),
+                    start_after: None,
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        let ids: Vec<u128> = projects.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![0, 2]);
+        assert!(projects.iter().all(|p| p.project.creator == alice));
+
+        let ListProjectsByCreatorResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjectsByCreator {
+                    creator: alice.to_string(),
+                    start_after: Some(2),
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        let ids: Vec<u128> = projects.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![3]);
+
+        let ListProjectsByCreatorResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjectsByCreator {
+                    creator: bob.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        let ids: Vec<u128> = projects.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_list_projects_filtered() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: "Too many tags".to_string(),
+                    tags: (0..=MAX_TAGS).map(|i| format!("tag{i}")).collect(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TooManyTags(MAX_TAGS)
+        );
+
+        for (name, tags) in [
+            ("Save the  Whales", vec!["Ocean", "animals"]),
+            ("save the trees", vec!["forest"]),
+            ("Solar Farm", vec!["energy"]),
+            ("Sea Cleanup", vec!["ocean"]),
+        ] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: tags.into_iter().map(String::from).collect(),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let list = |name_prefix: Option<&str>,
+                    tag: Option<&str>,
+                    start_after: Option<u128>,
+                    limit: Option<u32>| {
+            let ListProjectsResp { projects } = app
+                .wrap()
+                .query_wasm_smart(
+                    &contract,
+                    &QueryMsg::ListProjects {
+                        name_prefix: name_prefix.map(String::from),
+                        tag: tag.map(String::from),
+                        include_frozen: false,
+                        verified_only: false,
+                        start_after,
+                        limit,
+                    },
+                )
+                .unwrap();
+            projects.into_iter().map(|p| p.id).collect::<Vec<u128>>()
+        };
+
+        assert_eq!(list(None, None, None, None), vec![0, 1, 2, 3]);
+        assert_eq!(list(None, None, Some(1), Some(2)), vec![2, 3]);
+
+        // Ordered by normalized name: "save the trees" < "save the whales".
+        assert_eq!(list(Some("SAVE the"), None, None, None), vec![1, 0]);
+        assert_eq!(list(Some("save"), None, Some(1), Some(1)), vec![0]);
+        assert_eq!(list(Some("s"), None, None, None), vec![1, 0, 3, 2]);
+
+        assert_eq!(list(None, Some("OCEAN"), None, None), vec![0, 3]);
+        assert_eq!(list(Some("sea"), Some("ocean"), None, None), vec![3]);
+        assert!(list(None, Some("desert"), None, None).is_empty());
+    }
+
+    #[test]
+    fn test_unique_project_names() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let impostor = app.api().addr_make("impostor");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Save the Whales".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                impostor.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: "SAVE the whales!".to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ProjectNameTaken("save-the-whales".to_string())
+        );
+
+        let err = app
+            .execute_contract(
+                impostor.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: "!!!".to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidProjectName
+        );
+
+        // Clones with lookalike characters, a Cyrillic "а" or a capital "I" for an "l", collide too.
+        for (name, slug) in [
+            ("S\u{430}ve the Whales", "s\u{430}ve-the-whales"),
+            ("Save the WhaIes", "save-the-whaies"),
+        ] {
+            let err = app
+                .execute_contract(
+                    impostor.clone(),
+                    contract.clone(),
+                    &ExecuteMsg::CreateProject {
+                        name: name.to_string(),
+                        tags: vec![],
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::ProjectNameTaken(slug.to_string())
+            );
+        }
+
+        // Names are not limited to ASCII.
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Café Solidarité".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+        let ProjectResp { id, .. } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::GetProjectBySlug {
+                    slug: "café-solidarité".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(id, 1);
+
+        let ProjectResp { id, project, .. } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::GetProjectBySlug {
+                    slug: "save-the-whales".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(id, 0);
+        assert_eq!(project.creator, proj_owner);
+
+        // Only the contract owner can change the config.
+        let err = app
+            .execute_contract(
+                impostor.clone(),
+                contract.clone(),
+                &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                    unique_project_names: Some(false),
+                    ..Default::default()
+                }),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                unique_project_names: Some(false),
+                ..Default::default()
+            }),
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            impostor.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "SAVE the whales!".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Without the uniqueness rule, names without a slug are fine as well.
+        app.execute_contract(
+            impostor.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "!!!".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // The slug still points to the project that claimed it first.
+        let ProjectResp { id, .. } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::GetProjectBySlug {
+                    slug: "Save the Whales".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(id, 0);
+    }
+
+    #[test]
+    fn test_project_creation_fee_and_cap() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let new_owner = app.api().addr_make("new_owner");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &proj_owner, coins(20, "eth"))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &new_owner, coins(10, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                project_creation_fee: Some(Coin::new(5u128, "eth")),
+                max_active_projects_per_creator: Some(2),
+                ..Default::default()
+            }),
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: "Project A".to_string(),
+                    tags: vec![],
+                },
+                &coins(4, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidCreationFee(Coin::new(5u128, "eth"))
+        );
+
+        for name in ["Project A", "Project B"] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &coins(5, "eth"),
+            )
+            .unwrap();
+        }
+
+        let balance = app.wrap().query_balance(contract_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(10));
+        let balance = app.wrap().query_balance(contract.clone(), "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: "Project C".to_string(),
+                    tags: vec![],
+                },
+                &coins(5, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TooManyProjects(2)
+        );
+        let balance = app.wrap().query_balance(proj_owner.clone(), "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(10));
+
+        // Taking over an active project counts towards the cap as well.
+        for name in ["Project C", "Project D"] {
+            app.execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &coins(5, "eth"),
+            )
+            .unwrap();
+        }
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::ProposeProjectOwner {
+                project_id: 0,
+                new_owner: new_owner.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptProjectOwnership { project_id: 0 },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::TooManyProjects(2)
+        );
+
+        // An inactive project can be taken over.
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetProjectStatus {
+                project_id: 0,
+                status: ProjectStatus::Inactive,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            new_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::AcceptProjectOwnership { project_id: 0 },
+            &[],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_transfer_project_ownership() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let new_owner = app.api().addr_make("new_owner");
+        let treasurer = app.api().addr_make("treasurer");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(10, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetProjectManager {
+                project_id: 0,
+                manager: treasurer.to_string(),
+                roles: vec![ProjectRole::BeneficiaryManager],
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            treasurer.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetProjectBeneficiary {
+                project_id: 0,
+                beneficiary: Some(treasurer.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Only the creator can propose a new owner.
+        let err = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::ProposeProjectOwner {
+                    project_id: 0,
+                    new_owner: new_owner.to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        let err = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptProjectOwnership { project_id: 0 },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NoPendingOwner(0)
+        );
+
+        let resp = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::ProposeProjectOwner {
+                    project_id: 0,
+                    new_owner: new_owner.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+        assert!(resp
+            .events
+            .iter()
+            .any(|event| event.ty == "wasm-propose_project_owner"));
+
+        let PendingOwnershipTransfersResp { transfers } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::PendingOwnershipTransfers {
+                    pending_owner: Some(new_owner.to_string()),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            transfers,
+            vec![OwnershipTransfer {
+                project_id: 0,
+                owner: proj_owner.clone(),
+                pending_owner: new_owner.clone(),
+            }]
+        );
+
+        // Only the proposed owner can accept.
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptProjectOwnership { project_id: 0 },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        let resp = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptProjectOwnership { project_id: 0 },
+                &[],
+            )
+            .unwrap();
+        assert!(resp
+            .events
+            .iter()
+            .any(|event| event.ty == "wasm-accept_project_ownership"));
+
+        let ProjectResp { project, .. } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 0 })
+            .unwrap();
+        assert_eq!(project.creator, new_owner);
+        // The previous owner's managers and beneficiary do not carry over.
+        assert_eq!(project.beneficiary, None);
+
+        let ProjectManagersResp { managers } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::ProjectManagers { project_id: 0 })
+            .unwrap();
+        assert!(managers.is_empty());
+
+        let ListProjectsByCreatorResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjectsByCreator {
+                    creator: proj_owner.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(projects.is_empty());
+
+        let PendingOwnershipTransfersResp { transfers } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::PendingOwnershipTransfers {
+                    pending_owner: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(transfers.is_empty());
+
+        // Donations now pay out to the new owner.
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(10, "eth"),
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(new_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(9));
+        let balance = app.wrap().query_balance(proj_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+        let balance = app.wrap().query_balance(treasurer, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+    }
+
+    #[test]
+    fn test_project_managers() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let editor = app.api().addr_make("editor");
+        let treasurer = app.api().addr_make("treasurer");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(20, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec!["climate".to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Only the creator can assign roles.
+        let err = app
+            .execute_contract(
+                editor.clone(),
+                contract.clone(),
+                &ExecuteMsg::SetProjectManager {
+                    project_id: 0,
+                    manager: editor.to_string(),
+                    roles: vec![ProjectRole::StatusManager],
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        for (manager, roles) in [
+            (&editor, vec![ProjectRole::MetadataEditor]),
+            (
+                &treasurer,
+                vec![ProjectRole::BeneficiaryManager, ProjectRole::StatusManager],
+            ),
+        ] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::SetProjectManager {
+                    project_id: 0,
+                    manager: manager.to_string(),
+                    roles,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let ProjectManagersResp { managers } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::ProjectManagers { project_id: 0 })
+            .unwrap();
+        assert_eq!(managers.len(), 2);
+
+        app.execute_contract(
+            editor.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateProjectMetadata {
+                project_id: 0,
+                name: Some("Project Z".to_string()),
+                tags: Some(vec!["Oceans".to_string()]),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let ProjectResp { id, project, .. } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::GetProjectBySlug {
+                    slug: "project-z".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(id, 0);
+        assert_eq!(project.tags, vec!["oceans".to_string()]);
+
+        let ListProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: Some("climate".to_string()),
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(projects.is_empty());
+
+        // The old name is free again.
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // The editor cannot change the status.
+        let err = app
+            .execute_contract(
+                editor.clone(),
+                contract.clone(),
+                &ExecuteMsg::SetProjectStatus {
+                    project_id: 0,
+                    status: ProjectStatus::Inactive,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            treasurer.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetProjectBeneficiary {
+                project_id: 0,
+                beneficiary: Some(treasurer.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(10, "eth"),
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(treasurer.clone(), "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(9));
+        let balance = app.wrap().query_balance(proj_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+
+        app.execute_contract(
+            treasurer.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetProjectStatus {
+                project_id: 0,
+                status: ProjectStatus::Inactive,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ProjectNotActive(0)
+        );
+    }
+
+    #[test]
+    fn test_freeze_project() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let moderator = app.api().addr_make("moderator");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(20, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for name in ["Project A", "Project B"] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(10, "eth"),
+        )
+        .unwrap();
+
+        // Nobody but the owner and the moderators can freeze projects.
+        let err = app
+            .execute_contract(
+                moderator.clone(),
+                contract.clone(),
+                &ExecuteMsg::FreezeProject {
+                    project_id: 0,
+                    reason: "Scam".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateModerators {
+                add: vec![moderator.to_string()],
+                remove: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            moderator.clone(),
+            contract.clone(),
+            &ExecuteMsg::FreezeProject {
+                project_id: 0,
+                reason: "Scam".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ProjectFrozen(0)
+        );
+
+        let ListProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            projects.iter().map(|resp| resp.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        let ListProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjects {
+                    name_prefix: Some("project".to_string()),
+                    tag: None,
+                    include_frozen: true,
+                    verified_only: false,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(projects.len(), 2);
+
+        let ProjectResp { freeze, .. } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 0 })
+            .unwrap();
+        assert_eq!(freeze.unwrap().reason, "Scam");
+
+        let TopProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::TopProjects {
+                    denom: "eth".to_string(),
+                    include_frozen: false,
+                    limit: None,
+                    start_after: None,
+                },
+            )
+            .unwrap();
+        assert!(projects.is_empty());
+
+        let TopProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::TopProjects {
+                    denom: "eth".to_string(),
+                    include_frozen: true,
+                    limit: None,
+                    start_after: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].project_id, 0);
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::AppealFreeze {
+                project_id: 0,
+                statement: "We are legit".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Freezing the project again would lose the appeal.
+        let err = app
+            .execute_contract(
+                contract_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::FreezeProject {
+                    project_id: 0,
+                    reason: "Still a scam".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ProjectFrozen(0)
+        );
+
+        let ListFrozenProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListFrozenProjects {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].project_id, 0);
+        assert_eq!(projects[0].freeze.reason, "Scam");
+        assert_eq!(projects[0].freeze.frozen_by, moderator);
+        assert_eq!(projects[0].freeze.appeal, Some("We are legit".to_string()));
+
+        app.execute_contract(
+            moderator.clone(),
+            contract.clone(),
+            &ExecuteMsg::UnfreezeProject { project_id: 0 },
+            &[],
+        )
+        .unwrap();
+
+        let ProjectResp { freeze, .. } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 0 })
+            .unwrap();
+        assert_eq!(freeze, None);
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::AppealFreeze {
+                    project_id: 0,
+                    statement: "We are legit".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ProjectNotFrozen(0)
+        );
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(10, "eth"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let guardian = app.api().addr_make("guardian");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(10, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                guardian.clone(),
+                contract.clone(),
+                &ExecuteMsg::Pause {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                guardian: Some(Some(guardian.to_string())),
+                ..Default::default()
+            }),
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            guardian.clone(),
+            contract.clone(),
+            &ExecuteMsg::Pause {},
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(10, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Paused
+        );
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: "Project B".to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Paused
+        );
+
+        // The simulation fails just like the donation.
+        let err = app
+            .wrap()
+            .query_wasm_smart::<SimulateDonationResp>(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: coins(10, "eth"),
+                    sender: None,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    referrer: None,
+                    cover_fee: None,
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains(&ContractError::Paused.to_string()));
+
+        // Queries keep working.
+        let ProjectResp { id, .. } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 0 })
+            .unwrap();
+        assert_eq!(id, 0);
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::Unpause {},
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(10, "eth"),
+        )
+        .unwrap();
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                guardian: Some(None),
+                ..Default::default()
+            }),
+            &[],
+        )
+        .unwrap();
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.guardian, None);
+
+        let err = app
+            .execute_contract(guardian, contract, &ExecuteMsg::Pause {}, &[])
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+    }
+
+    #[test]
+    fn test_ownership() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let new_owner = app.api().addr_make("new_owner");
+        let impostor = app.api().addr_make("impostor");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        let err = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptOwnership {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NoPendingOwnership
+        );
+
+        // A transfer that is not accepted in time lapses.
+        let expires_at = app.block_info().height + 10;
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::TransferOwnership {
+                new_owner: new_owner.to_string(),
+                expiry: Some(Expiration::AtHeight(expires_at)),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let ownership: Ownership = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::Ownership {})
+            .unwrap();
+        assert_eq!(ownership.owner, Some(contract_owner.clone()));
+        assert_eq!(ownership.pending_owner, Some(new_owner.clone()));
+
+        app.update_block(|block| block.height += 10);
+        let err = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptOwnership {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::OwnershipTransferExpired
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::TransferOwnership {
+                new_owner: new_owner.to_string(),
+                expiry: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                impostor.clone(),
+                contract.clone(),
+                &ExecuteMsg::AcceptOwnership {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            new_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::AcceptOwnership {},
+            &[],
+        )
+        .unwrap();
+
+        let ownership: Ownership = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::Ownership {})
+            .unwrap();
+        assert_eq!(
+            ownership,
+            Ownership {
+                owner: Some(new_owner.clone()),
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        );
+
+        // The previous owner lost its powers.
+        let err = app
+            .execute_contract(
+                contract_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::Pause {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            new_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::RenounceOwnership {},
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                new_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::Pause {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        let ownership: Ownership = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::Ownership {})
+            .unwrap();
+        assert_eq!(ownership.owner, None);
+    }
+
+    #[test]
+    fn test_verify_project() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for name in ["Project A", "Project B"] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::VerifyProject {
+                    project_id: 1,
+                    attestation_uri: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::VerifyProject {
+                project_id: 1,
+                attestation_uri: Some("ipfs://attestation".to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let ProjectResp { verification, .. } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 1 })
+            .unwrap();
+        let verification = verification.unwrap();
+        assert_eq!(verification.verified_by, contract_owner);
+        assert_eq!(
+            verification.attestation_uri,
+            Some("ipfs://attestation".to_string())
+        );
+
+        let ListProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjects {
+                    name_prefix: None,
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: true,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            projects.iter().map(|resp| resp.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::RevokeVerification { project_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+        let ListProjectsResp { projects } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ListProjects {
+                    name_prefix: Some("project".to_string()),
+                    tag: None,
+                    include_frozen: false,
+                    verified_only: true,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(projects.is_empty());
+
+        let err = app
+            .execute_contract(
+                contract_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::RevokeVerification { project_id: 1 },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ProjectNotVerified(1)
+        );
+    }
+
+    #[test]
+    fn test_fee_override() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(200, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::SetFeeOverride {
+                    project_id: 0,
+                    fee_rate: Some(Decimal::zero()),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized
+        );
+
+        let err = app
+            .execute_contract(
+                contract_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::SetFeeOverride {
+                    project_id: 0,
+                    fee_rate: Some(Decimal::percent(101)),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidFeeRate(Decimal::percent(101))
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetFeeOverride {
+                project_id: 0,
+                fee_rate: Some(Decimal::percent(1)),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let ProjectResp { fee_override, .. } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::GetProject { project_id: 0 })
+            .unwrap();
+        assert_eq!(fee_override, Some(Decimal::percent(1)));
+
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(100, "eth"),
+            )
+            .unwrap();
+        let event = resp
+            .events
+            .iter()
+            .find(|event| event.ty == "wasm-donation")
+            .unwrap();
+        assert!(event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "fee_rate" && attr.value == "0.01"));
+
+        let balance = app.wrap().query_balance(proj_owner.clone(), "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(99));
+        let balance = app
+            .wrap()
+            .query_balance(contract_owner.clone(), "eth")
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(1));
+
+        // A zero fee override sends everything to the project.
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::SetFeeOverride {
+                project_id: 0,
+                fee_rate: Some(Decimal::zero()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let SimulateDonationResp { coins: splits, .. } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: coins(100, "eth"),
+                    sender: None,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    referrer: None,
+                    cover_fee: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            splits,
+            vec![CoinSplit {
+                gross: Coin::new(100u128, "eth"),
+                net: Coin::new(100u128, "eth"),
+                fee: Coin::new(0u128, "eth"),
+                tier: FeeTier::Override(Decimal::zero()),
+                referral: None,
+            }]
+        );
+
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: None,
+            },
+            &coins(100, "eth"),
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(proj_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(199));
+        let balance = app.wrap().query_balance(contract_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(1));
+    }
+
+    #[test]
+    fn test_fee_collectors() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let treasury = app.api().addr_make("treasury");
+        let partner = app.api().addr_make("partner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(100, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                contract_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                    fee_collectors: Some(vec![(treasury.to_string(), 0)]),
+                    ..Default::default()
+                }),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidFeeCollectors
+        );
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                fee_collectors: Some(vec![(treasury.to_string(), 2), (partner.to_string(), 1)]),
+                ..Default::default()
+            }),
+            &[],
+        )
+        .unwrap();
+
+        let resp = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(100, "eth"),
+            )
+            .unwrap();
+
+        // One transfer per recipient: the creator and both fee collectors.
+        let transfers = resp.events.iter().filter(|e| e.ty == "transfer").count();
+        assert_eq!(transfers, 3);
+
+        // The 10eth fee is split 2:1, with the remainder going to the first collector.
+        let balance = app.wrap().query_balance(proj_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(90));
+        let balance = app.wrap().query_balance(treasury, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(7));
+        let balance = app.wrap().query_balance(partner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(3));
+        let balance = app.wrap().query_balance(contract_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+    }
+
+    #[test]
+    fn test_referral() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let referrer = app.api().addr_make("referrer");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(300, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Referrers earn nothing until the contract owner sets a share.
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: Some(referrer.to_string()),
+                cover_fee: None,
+            },
+            &coins(100, "eth"),
+        )
+        .unwrap();
+        let ReferralEarningsResp { earnings } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ReferralEarnings {
+                    referrer: referrer.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(earnings.is_empty());
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate {
+                referral_share: Some(Decimal::percent(30)),
+                ..Default::default()
+            }),
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: Some(patron.to_string()),
+                    cover_fee: None,
+                },
+                &coins(100, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::SelfReferral
+        );
+
+        let err = app
+            .wrap()
+            .query_wasm_smart::<SimulateDonationResp>(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: coins(100, "eth"),
+                    sender: Some(patron.to_string()),
+                    on_behalf_of: None,
+                    anonymous: false,
+                    referrer: Some(patron.to_string()),
+                    cover_fee: None,
+                },
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&ContractError::SelfReferral.to_string()));
+
+        // The patron the donation is made for cannot refer it either.
+        let err = app
+            .wrap()
+            .query_wasm_smart::<SimulateDonationResp>(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: coins(100, "eth"),
+                    sender: Some(patron.to_string()),
+                    on_behalf_of: Some(referrer.to_string()),
+                    anonymous: false,
+                    referrer: Some(referrer.to_string()),
+                    cover_fee: None,
+                },
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&ContractError::SelfReferral.to_string()));
+
+        let SimulateDonationResp { coins: splits, .. } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: coins(100, "eth"),
+                    sender: Some(patron.to_string()),
+                    on_behalf_of: None,
+                    anonymous: false,
+                    referrer: Some(referrer.to_string()),
+                    cover_fee: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(splits[0].referral, Some(Coin::new(3u128, "eth")));
+
+        for _ in 0..2 {
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: Some(referrer.to_string()),
+                    cover_fee: None,
+                },
+                &coins(100, "eth"),
+            )
+            .unwrap();
+        }
+
+        // 30% of each 10eth fee goes to the referrer.
+        let balance = app.wrap().query_balance(proj_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(270));
+        let balance = app.wrap().query_balance(referrer.clone(), "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(6));
+        let balance = app.wrap().query_balance(contract_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(24));
+
+        let ReferralEarningsResp { earnings } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::ReferralEarnings {
+                    referrer: referrer.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(earnings, coins(6, "eth"));
+    }
+
+    #[test]
+    fn test_donate_cover_fee() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let proj_owner = app.api().addr_make("proj_owner");
+        let patron = app.api().addr_make("patron");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &patron, coins(150, "eth"))
+                .unwrap();
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            proj_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateProject {
+                name: "Project A".to_string(),
+                tags: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Above the threshold the fee on top is 5% of the donation.
+        let SimulateDonationResp {
+            coins: splits,
+            refund,
+            ..
+        } = app
+            .wrap()
+            .query_wasm_smart(
+                &contract,
+                &QueryMsg::SimulateDonation {
+                    project_id: 0,
+                    funds: coins(12_000, "eth"),
+                    sender: None,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    referrer: None,
+                    cover_fee: Some(coins(10_476, "eth")),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            splits,
+            vec![CoinSplit {
+                gross: Coin::new(11_000u128, "eth"),
+                net: Coin::new(10_476u128, "eth"),
+                fee: Coin::new(524u128, "eth"),
+                tier: FeeTier::AboveThreshold,
+                referral: None,
+            }]
+        );
+        assert_eq!(refund, coins(1_000, "eth"));
+
+        let err = app
+            .execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: Some(coins(100, "eth")),
+                },
+                &coins(109, "eth"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::FeeNotCovered("eth".to_string())
+        );
+
+        // 100eth plus the 10eth fee leaves 40eth to refund.
+        app.execute_contract(
+            patron.clone(),
+            contract.clone(),
+            &ExecuteMsg::Donate {
+                project_id: 0,
+                on_behalf_of: None,
+                anonymous: false,
+                memo: None,
+                referrer: None,
+                cover_fee: Some(coins(100, "eth")),
+            },
+            &coins(150, "eth"),
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(proj_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(100));
+        let balance = app.wrap().query_balance(contract_owner, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(10));
+        let balance = app.wrap().query_balance(patron, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(40));
+        let balance = app.wrap().query_balance(contract.clone(), "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+
+        let ProjectTotalsResp { totals } = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::ProjectTotals { project_id: 0 })
+            .unwrap();
+        assert_eq!(totals, coins(110, "eth"));
+    }
+
+    #[test]
+    fn test_round_matching_on_behalf_of() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let operator = app.api().addr_make("operator");
+        let proj_owner_a = app.api().addr_make("proj_owner_a");
+        let proj_owner_b = app.api().addr_make("proj_owner_b");
+        let funder = app.api().addr_make("funder");
+        let patrons: Vec<Addr> = (0..2)
+            .map(|i| app.api().addr_make(&format!("patron{i}")))
+            .collect();
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &operator, coins(100, "eth"))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &funder, coins(8, "eth"))
+                .unwrap();
+            for patron in &patrons {
+                router
+                    .bank
+                    .init_balance(storage, patron, coins(4, "eth"))
+                    .unwrap();
+            }
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for (proj_owner, name) in [(&proj_owner_a, "Project A"), (&proj_owner_b, "Project B")] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let now = app.block_info().time;
+        app.execute_contract(
+            operator.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateRound {
+                name: "Round".to_string(),
+                start: now,
+                end: now.plus_seconds(100),
+            },
+            &coins(100, "eth"),
+        )
+        .unwrap();
+
+        for (project_id, proj_owner) in [(0, &proj_owner_a), (1, &proj_owner_b)] {
+            app.execute_contract(
+                operator.clone(),
+                contract.clone(),
+                &ExecuteMsg::RegisterRoundProject {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::JoinRound {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        // The funder splits 8eth to project A over two made-up patrons.
+        for name in ["sybil0", "sybil1"] {
+            app.execute_contract(
+                funder.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 0,
+                    on_behalf_of: Some(app.api().addr_make(name).to_string()),
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(4, "eth"),
+            )
+            .unwrap();
+        }
+
+        // Two real patrons give 4eth each to project B.
+        for patron in &patrons {
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id: 1,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(4, "eth"),
+            )
+            .unwrap();
+        }
+
+        app.update_block(|block| block.time = block.time.plus_seconds(100));
+        app.execute_contract(
+            operator.clone(),
+            contract.clone(),
+            &ExecuteMsg::FinalizeRound { round_id: 0 },
+            &[],
+        )
+        .unwrap();
+
+        // Project A has a single funder, so its weight is (sqrt(8))^2 - 8 = 0 and the whole pool goes to B.
+        let balance = app.wrap().query_balance(proj_owner_a, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(6));
+        let balance = app.wrap().query_balance(proj_owner_b, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(106));
+    }
+
+    #[test]
+    fn test_round_skips_frozen_projects() {
+        let mut app = App::default();
+
+        let contract_owner = app.api().addr_make("contract_owner");
+        let operator = app.api().addr_make("operator");
+        let proj_owner_a = app.api().addr_make("proj_owner_a");
+        let proj_owner_b = app.api().addr_make("proj_owner_b");
+        let patrons: Vec<Addr> = (0..4)
+            .map(|i| app.api().addr_make(&format!("patron{i}")))
+            .collect();
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &operator, coins(100, "eth"))
+                .unwrap();
+            for patron in &patrons {
+                router
+                    .bank
+                    .init_balance(storage, patron, coins(4, "eth"))
+                    .unwrap();
+            }
+        });
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                contract_owner.clone(),
+                &Empty {},
+                &[],
+                "Donations contract",
+                None,
+            )
+            .unwrap();
+
+        for (proj_owner, name) in [(&proj_owner_a, "Project A"), (&proj_owner_b, "Project B")] {
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::CreateProject {
+                    name: name.to_string(),
+                    tags: vec![],
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        let now = app.block_info().time;
+        app.execute_contract(
+            operator.clone(),
+            contract.clone(),
+            &ExecuteMsg::CreateRound {
+                name: "Round".to_string(),
+                start: now,
+                end: now.plus_seconds(100),
+            },
+            &coins(100, "eth"),
+        )
+        .unwrap();
+
+        for (project_id, proj_owner) in [(0, &proj_owner_a), (1, &proj_owner_b)] {
+            app.execute_contract(
+                operator.clone(),
+                contract.clone(),
+                &ExecuteMsg::RegisterRoundProject {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                proj_owner.clone(),
+                contract.clone(),
+                &ExecuteMsg::JoinRound {
+                    round_id: 0,
+                    project_id,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        // Both projects get the same weight: (sqrt(4) + sqrt(4))^2 - 8 = 8.
+        for (patron, project_id) in patrons.iter().zip([0, 0, 1, 1]) {
+            app.execute_contract(
+                patron.clone(),
+                contract.clone(),
+                &ExecuteMsg::Donate {
+                    project_id,
+                    on_behalf_of: None,
+                    anonymous: false,
+                    memo: None,
+                    referrer: None,
+                    cover_fee: None,
+                },
+                &coins(4, "eth"),
+            )
+            .unwrap();
+        }
+
+        app.execute_contract(
+            contract_owner.clone(),
+            contract.clone(),
+            &ExecuteMsg::FreezeProject {
+                project_id: 0,
+                reason: "Fraud report".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.update_block(|block| block.time = block.time.plus_seconds(100));
+        app.execute_contract(
+            operator.clone(),
+            contract.clone(),
+            &ExecuteMsg::FinalizeRound { round_id: 0 },
+            &[],
+        )
+        .unwrap();
+
+        // Project A is frozen, so the whole pool goes to B.
+        let balance = app.wrap().query_balance(proj_owner_a, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(6));
+        let balance = app.wrap().query_balance(proj_owner_b, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::new(106));
+        let balance = app.wrap().query_balance(operator, "eth").unwrap();
+        assert_eq!(balance.amount, Uint128::zero());
+    }
 }
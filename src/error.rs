@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, CoinsError, Decimal, StdError};
 use cw_utils::PaymentError;
 use thiserror::Error;
 
@@ -7,10 +7,78 @@ use thiserror::Error;
 pub enum ContractError {
     #[error("{0}")]
     StdError(#[from] StdError),
+    #[error("{0}")]
+    Coins(#[from] CoinsError),
     #[error("Payment error: {0}")]
     Payment(#[from] PaymentError),
     #[error("{0}")]
     NonexistentProjectId(#[from] NonexistentProjectIdError),
+    #[error("{0}")]
+    NonexistentRoundId(#[from] NonexistentRoundIdError),
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("The round must end after it starts and after the current block time")]
+    InvalidRoundWindow,
+    #[error("Round with id {0} has already ended")]
+    RoundEnded(u128),
+    #[error("Round with id {0} has not ended yet")]
+    RoundNotEnded(u128),
+    #[error("Round with id {0} has already been finalized")]
+    RoundFinalized(u128),
+    #[error("Project {1} is not invited to round {0}")]
+    NotInvitedToRound(u128, u128),
+    #[error("A round can have at most {0} projects")]
+    TooManyRoundProjects(usize),
+    #[error("The allocations must add up exactly to the attached funds")]
+    AllocationsMismatch,
+    #[error("Anonymous donations cannot be made on behalf of another address")]
+    AnonymousOnBehalfOf,
+    #[error("The memo must not be longer than {0} bytes")]
+    MemoTooLong(usize),
+    #[error("The memo must not be empty")]
+    EmptyMemo,
+    #[error("Invalid tag \"{0}\"")]
+    InvalidTag(String),
+    #[error("A project can have at most {0} tags")]
+    TooManyTags(usize),
+    #[error("The project name must contain at least one letter or digit")]
+    InvalidProjectName,
+    #[error("A project with the slug \"{0}\" already exists")]
+    ProjectNameTaken(String),
+    #[error("No project has the slug \"{0}\"")]
+    NonexistentSlug(String),
+    #[error("Creating a project costs exactly {0}")]
+    InvalidCreationFee(Coin),
+    #[error("An address can have at most {0} active projects")]
+    TooManyProjects(u32),
+    #[error("No ownership transfer is pending for project {0}")]
+    NoPendingOwner(u128),
+    #[error("Project {0} is not active")]
+    ProjectNotActive(u128),
+    #[error("Project {0} is frozen")]
+    ProjectFrozen(u128),
+    #[error("Project {0} is not frozen")]
+    ProjectNotFrozen(u128),
+    #[error("The text must be between 1 and {0} bytes long")]
+    InvalidText(usize),
+    #[error("The contract is paused")]
+    Paused,
+    #[error("No ownership transfer is pending")]
+    NoPendingOwnership,
+    #[error("The ownership transfer has expired")]
+    OwnershipTransferExpired,
+    #[error("Project {0} is not verified")]
+    ProjectNotVerified(u128),
+    #[error("The fee rate {0} is above 1")]
+    InvalidFeeRate(Decimal),
+    #[error(
+        "The fee collectors must be distinct addresses with positive weights, and at least one"
+    )]
+    InvalidFeeCollectors,
+    #[error("The patron cannot be its own referrer")]
+    SelfReferral,
+    #[error("The funds in {0} do not cover the donation and the fee")]
+    FeeNotCovered(String),
 }
 
 #[derive(Error)]
@@ -18,4 +86,9 @@ pub enum ContractError {
 #[error("Project with id {0} does not exist")]
 pub struct NonexistentProjectIdError(pub u128);
 
+#[derive(Error)]
+#[cw_serde]
+#[error("Round with id {0} does not exist")]
+pub struct NonexistentRoundIdError(pub u128);
+
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -1,128 +1,1569 @@
-use cosmwasm_std::{Addr, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+    Addr, Api, BankMsg, Coin, Coins, Decimal, Decimal256, DepsMut, Env, Event, MessageInfo, Order,
+    Response, StdResult, Storage, Timestamp, Uint128, Uint256,
+};
 
-use crate::contract::THRESHOLD;
-use crate::error::ContractResult;
-use crate::msg::ExecuteMsg;
-use crate::state::{DonationTx, Project, AUTHOR, DONATIONS, PROJECTS, PROJECT_COUNT};
+use crate::contract::{
+    MAX_MEMO_LEN, MAX_ROUND_PROJECTS, MAX_TAGS, MAX_TAG_LEN, SECONDS_PER_DAY, THRESHOLD,
+};
+use crate::error::{
+    ContractError, ContractResult, NonexistentProjectIdError, NonexistentRoundIdError,
+};
+use crate::msg::{CoinSplit, ConfigUpdate, ExecuteMsg, FeeTier};
+use crate::state::{
+    name_index_key, normalize_name, slug_key, slugify, Config, DenomStats, DonationTx,
+    FeeCollector, Freeze, Ownership, Project, ProjectRole, ProjectStatus, Round, RoundTally,
+    Verification, CONFIG, DENOM_STATS, DONATIONS, DONATION_BUCKETS, FEE_OVERRIDES, FROZEN_PROJECTS,
+    MODERATORS, OWNERSHIP, PATRONS, PATRON_COUNT, PATRON_TOTALS, PENDING_PROJECT_OWNERS, PROJECTS,
+    PROJECTS_BY_NAME, PROJECTS_BY_TAG, PROJECT_COUNT, PROJECT_MANAGERS, PROJECT_ROUNDS,
+    PROJECT_SLUGS, PROJECT_TOTALS, REFERRAL_EARNINGS, ROUNDS, ROUND_CONTRIBUTIONS, ROUND_COUNT,
+    ROUND_INVITATIONS, ROUND_PROJECTS, ROUND_TALLIES, TOP_DONORS, VERIFICATIONS,
+};
+use cw_utils::Expiration;
+use payouts::Payouts;
 
-pub fn create_project(deps: &mut DepsMut, name: String, creator: Addr) -> ContractResult<()> {
-    // TODO: implement the .push() for the structure representing the pair (PROJECTS, PROJECT_COUNT)
-    let project_count = PROJECT_COUNT.load(deps.storage)?;
-    PROJECT_COUNT.save(deps.storage, &(project_count + 1))?;
+fn load_project(storage: &dyn Storage, project_id: u128) -> ContractResult<Project> {
+    PROJECTS
+        .may_load(storage, project_id)?
+        .ok_or_else(|| NonexistentProjectIdError(project_id).into())
+}
+
+fn load_round(storage: &dyn Storage, round_id: u128) -> ContractResult<Round> {
+    ROUNDS
+        .may_load(storage, round_id)?
+        .ok_or_else(|| NonexistentRoundIdError(round_id).into())
+}
 
-    PROJECTS.save(deps.storage, project_count, &Project::new(name, creator))?;
+/// Lowercases the tags and removes duplicates.
+fn normalize_tags(tags: Vec<String>) -> ContractResult<Vec<String>> {
+    let mut normalized: Vec<String> = vec![];
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tag.len() > MAX_TAG_LEN || tag.chars().any(char::is_control) {
+            return Err(ContractError::InvalidTag(tag));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(ContractError::TooManyTags(MAX_TAGS));
+    }
+    Ok(normalized)
+}
 
+/// Adds the project to the name and tag indexes.
+fn index_project(storage: &mut dyn Storage, project_id: u128, project: &Project) -> StdResult<()> {
+    let key = name_index_key(&normalize_name(&project.name), project_id);
+    PROJECTS_BY_NAME.save(storage, key, &())?;
+    for tag in &project.tags {
+        PROJECTS_BY_TAG.save(storage, (tag, project_id), &())?;
+    }
     Ok(())
 }
 
-fn split_by_recipient(mut funds: Vec<Coin>) -> split_by_recipient::Output {
-    let for_project_creator: Vec<Coin> = funds
-        .iter()
-        .map(|coin| {
-            let amount = match coin.amount.u128() {
-                // The payment is 90% of the donation
-                0..=THRESHOLD => coin.amount * Uint128::new(9) / Uint128::new(10u128),
-                // The payment is 95% of the donation
-                _ => coin.amount * Uint128::new(19) / Uint128::new(20),
-            };
-            let denom = coin.denom.clone();
-            Coin { denom, amount }
-        })
-        .collect();
+/// Removes the project from the name, tag and slug indexes.
+fn unindex_project(
+    storage: &mut dyn Storage,
+    project_id: u128,
+    project: &Project,
+) -> StdResult<()> {
+    let key = name_index_key(&normalize_name(&project.name), project_id);
+    PROJECTS_BY_NAME.remove(storage, key);
+    for tag in &project.tags {
+        PROJECTS_BY_TAG.remove(storage, (tag, project_id));
+    }
+    let key = slug_key(&slugify(&project.name));
+    if PROJECT_SLUGS.may_load(storage, &key)? == Some(project_id) {
+        PROJECT_SLUGS.remove(storage, &key);
+    }
+    Ok(())
+}
 
-    // modify the original funds to deduct the amount for the project creator, leaving the remainder for the contract author
-    for (coin_for_contract_author, coin_for_project_creator) in
-        funds.iter_mut().zip(for_project_creator.iter())
-    {
-        coin_for_contract_author.amount -= coin_for_project_creator.amount;
+/// Claims the slug of the project name, unless another project has already claimed it.
+///
+/// The name is only checked when project names must be unique.
+fn claim_slug(storage: &mut dyn Storage, name: &str, project_id: u128) -> ContractResult<()> {
+    let unique_names = CONFIG.load(storage)?.unique_project_names;
+    let slug = slugify(name);
+    if slug.is_empty() {
+        if unique_names {
+            return Err(ContractError::InvalidProjectName);
+        }
+        return Ok(());
+    }
+    let key = slug_key(&slug);
+    if PROJECT_SLUGS.has(storage, &key) {
+        if unique_names {
+            return Err(ContractError::ProjectNameTaken(slug));
+        }
+        return Ok(());
     }
+    PROJECT_SLUGS.save(storage, &key, &project_id)?;
+    Ok(())
+}
 
-    split_by_recipient::Output {
-        for_project_creator,
-        for_contract_author: funds,
+/// Checks that the creation fee, if any, is paid exactly and returns the transfer of the fee to the fee collector.
+fn collect_creation_fee(info: &MessageInfo, config: &Config) -> ContractResult<Payouts> {
+    let mut payouts = Payouts::default();
+    let Some(fee) = &config.project_creation_fee else {
+        cw_utils::nonpayable(info)?;
+        return Ok(payouts);
+    };
+    if cw_utils::must_pay(info, &fee.denom)? != fee.amount {
+        return Err(ContractError::InvalidCreationFee(fee.clone()));
     }
+    payouts.add_shared(&config.fee_collectors, [fee.clone()])?;
+    Ok(payouts)
+}
+
+fn assert_active_projects_below_cap(
+    storage: &dyn Storage,
+    config: &Config,
+    creator: &Addr,
+) -> ContractResult<()> {
+    if let Some(max) = config.max_active_projects_per_creator {
+        if count_active_projects(storage, creator)? >= max as usize {
+            return Err(ContractError::TooManyProjects(max));
+        }
+    }
+    Ok(())
+}
+
+fn count_active_projects(storage: &dyn Storage, creator: &Addr) -> StdResult<usize> {
+    PROJECTS
+        .idx
+        .creator
+        .prefix(creator.clone())
+        .range(storage, None, None, Order::Ascending)
+        .try_fold(0, |count, item| {
+            let (_id, project) = item?;
+            Ok(count + usize::from(project.status == ProjectStatus::Active))
+        })
+}
+
+pub fn create_project(
+    deps: &mut DepsMut,
+    info: &MessageInfo,
+    name: String,
+    tags: Vec<String>,
+) -> ContractResult<Response> {
+    let creator = info.sender.clone();
+    let tags = normalize_tags(tags)?;
+
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let payouts = collect_creation_fee(info, &config)?;
+    assert_active_projects_below_cap(deps.storage, &config, &creator)?;
+
+    // TODO: implement the .push() for the structure representing the pair (PROJECTS, PROJECT_COUNT)
+    let project_count = PROJECT_COUNT.load(deps.storage)?;
+    PROJECT_COUNT.save(deps.storage, &(project_count + 1))?;
+
+    claim_slug(deps.storage, &name, project_count)?;
+
+    let project = Project::new(name, creator, tags);
+    PROJECTS.save(deps.storage, project_count, &project)?;
+    index_project(deps.storage, project_count, &project)?;
+
+    let resp = payouts
+        .into_response()
+        .add_attribute("action", "create_project")
+        .add_attribute("project_id", project_count.to_string());
+    Ok(resp)
+}
+
+fn split_coin(
+    coin: &Coin,
+    fee_override: Option<Decimal>,
+    referral_share: Option<Decimal>,
+) -> CoinSplit {
+    let (tier, net_amount) = match (fee_override, coin.amount.u128()) {
+        // The project pays the fee rate the contract owner set for it
+        (Some(fee_rate), _) => (
+            FeeTier::Override(fee_rate),
+            coin.amount.mul_floor(Decimal::one() - fee_rate),
+        ),
+        // The payment is 90% of the donation
+        (None, 0..=THRESHOLD) => (
+            FeeTier::BelowThreshold,
+            coin.amount * Uint128::new(9) / Uint128::new(10u128),
+        ),
+        // The payment is 95% of the donation
+        (None, _) => (
+            FeeTier::AboveThreshold,
+            coin.amount * Uint128::new(19) / Uint128::new(20),
+        ),
+    };
+    let denom = coin.denom.clone();
+    let fee_amount = coin.amount - net_amount;
+    CoinSplit {
+        gross: coin.clone(),
+        net: Coin::new(net_amount, denom.clone()),
+        // the remainder goes to the fee collectors, except for the referrer's share
+        fee: Coin::new(fee_amount, denom.clone()),
+        tier,
+        referral: referral_share
+            .map(|share| Coin::new(fee_amount.mul_floor(share), denom))
+            .filter(|referral| !referral.amount.is_zero()),
+    }
+}
+
+/// Splits the donation of `net` when the patron covers the fee: the project receives all of `net`
+/// and the fee a donation of `net` would pay comes on top of it.
+fn cover_coin(
+    net: &Coin,
+    fee_override: Option<Decimal>,
+    referral_share: Option<Decimal>,
+) -> StdResult<CoinSplit> {
+    let CoinSplit {
+        fee,
+        tier,
+        referral,
+        ..
+    } = split_coin(net, fee_override, referral_share);
+    Ok(CoinSplit {
+        gross: Coin::new(net.amount.checked_add(fee.amount)?, &net.denom),
+        net: net.clone(),
+        fee,
+        tier,
+        referral,
+    })
+}
+
+/// `referral_share` is the share of the fee that goes to the referrer, if there is one.
+/// If `cover_fee` is set, it holds the amounts the project receives and the fee comes on top of them.
+fn split_by_recipient(
+    funds: &[Coin],
+    fee_override: Option<Decimal>,
+    referral_share: Option<Decimal>,
+    cover_fee: Option<&[Coin]>,
+) -> StdResult<split_by_recipient::Output> {
+    let splits = match cover_fee {
+        Some(nets) => nets
+            .iter()
+            .map(|net| cover_coin(net, fee_override, referral_share))
+            .collect::<StdResult<_>>()?,
+        None => funds
+            .iter()
+            .map(|coin| split_coin(coin, fee_override, referral_share))
+            .collect(),
+    };
+    Ok(split_by_recipient::Output { splits })
 }
 
 mod split_by_recipient {
-    use cosmwasm_std::{Addr, BankMsg, Coin};
+    use cosmwasm_std::{Addr, Coin, StdResult, Uint128};
+
+    use super::payouts::Payouts;
+    use crate::msg::CoinSplit;
+    use crate::state::FeeCollector;
 
     pub(super) struct Output {
-        pub(super) for_project_creator: Vec<Coin>,
-        pub(super) for_contract_author: Vec<Coin>,
+        pub(super) splits: Vec<CoinSplit>,
     }
 
     impl Output {
-        fn into_bank_message_iter(
-            self,
-            project_creator: Addr,
-            contract_author: Addr,
-        ) -> impl Iterator<Item = cosmwasm_std::BankMsg> {
-            let project_creator_msg = cosmwasm_std::BankMsg::Send {
-                to_address: project_creator.to_string(),
-                amount: self.for_project_creator,
-            };
+        pub(super) fn add_to(
+            &self,
+            payouts: &mut Payouts,
+            project_creator: &Addr,
+            fee_collectors: &[FeeCollector],
+            referrer: Option<&Addr>,
+        ) -> StdResult<()> {
+            payouts.add(
+                project_creator,
+                self.splits.iter().map(|split| split.net.clone()),
+            )?;
+            if let Some(referrer) = referrer {
+                payouts.add(
+                    referrer,
+                    self.splits
+                        .iter()
+                        .filter_map(|split| split.referral.clone()),
+                )?;
+            }
+            payouts.add_shared(
+                fee_collectors,
+                self.splits.iter().map(|split| {
+                    let referral = split
+                        .referral
+                        .as_ref()
+                        .map_or(Uint128::zero(), |c| c.amount);
+                    Coin::new(split.fee.amount - referral, split.fee.denom.clone())
+                }),
+            )?;
+            Ok(())
+        }
+    }
+}
 
-            let contract_author_msg = cosmwasm_std::BankMsg::Send {
-                to_address: contract_author.to_string(),
-                amount: self.for_contract_author,
+/// Splits the donation to the project and adds the resulting transfers to `payouts`.
+///
+/// `donate`, `donate_many` and the `SimulateDonation` query all go through this function,
+/// so a simulation always matches the actual donation. Fails if the contract is paused
+/// or the project is not active or frozen.
+///
+/// When the patron covers the fee, the splits are made over the amounts in `cover_fee` and
+/// the funds must hold every gross amount. Whatever is left over is refunded, see `refunds`.
+pub(crate) fn settle_donation(
+    storage: &dyn Storage,
+    project_id: u128,
+    project: &Project,
+    funds: &[Coin],
+    referrer: Option<&Addr>,
+    cover_fee: Option<&[Coin]>,
+    payouts: &mut Payouts,
+) -> ContractResult<Vec<CoinSplit>> {
+    assert_not_paused(storage)?;
+    if project.status != ProjectStatus::Active {
+        return Err(ContractError::ProjectNotActive(project_id));
+    }
+    if FROZEN_PROJECTS.has(storage, project_id) {
+        return Err(ContractError::ProjectFrozen(project_id));
+    }
+    let config = CONFIG.load(storage)?;
+    let fee_override = FEE_OVERRIDES.may_load(storage, project_id)?;
+    let referral_share = referrer.map(|_| config.referral_share);
+    let nets = cover_fee
+        .map(|nets| Coins::try_from(nets.to_vec()))
+        .transpose()?
+        .map(Coins::into_vec);
+    let output = split_by_recipient(funds, fee_override, referral_share, nets.as_deref())?;
+    for split in &output.splits {
+        let sent = funds
+            .iter()
+            .find(|coin| coin.denom == split.gross.denom)
+            .map_or(Uint128::zero(), |coin| coin.amount);
+        if sent < split.gross.amount {
+            return Err(ContractError::FeeNotCovered(split.gross.denom.clone()));
+        }
+    }
+    output.add_to(
+        payouts,
+        project.recipient(),
+        &config.fee_collectors,
+        referrer,
+    )?;
+    Ok(output.splits)
+}
+
+/// The part of every coin of the funds that the splits leave over.
+pub(crate) fn refunds(funds: &[Coin], splits: &[CoinSplit]) -> Vec<Coin> {
+    funds
+        .iter()
+        .map(|coin| {
+            let donated = splits
+                .iter()
+                .find(|split| split.gross.denom == coin.denom)
+                .map_or(Uint128::zero(), |split| split.gross.amount);
+            Coin::new(coin.amount - donated, &coin.denom)
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect()
+}
+
+pub(crate) mod payouts {
+    use cosmwasm_std::{Addr, BankMsg, Coin, Coins, Response, StdResult, Uint128};
+
+    use crate::msg::Payout;
+    use crate::state::FeeCollector;
+
+    /// The bank transfers of a single execute message, aggregated per recipient so that
+    /// every address receives at most one `BankMsg::Send`.
+    #[derive(Default)]
+    pub(crate) struct Payouts(Vec<(Addr, Coins)>);
+
+    impl Payouts {
+        pub(crate) fn add(
+            &mut self,
+            recipient: &Addr,
+            coins: impl IntoIterator<Item = Coin>,
+        ) -> StdResult<()> {
+            // A linear search keeps the messages in the order in which the recipients were first added.
+            let idx = match self.0.iter().position(|(addr, _)| addr == recipient) {
+                Some(idx) => idx,
+                None => {
+                    self.0.push((recipient.clone(), Coins::default()));
+                    self.0.len() - 1
+                }
             };
+            for coin in coins {
+                self.0[idx].1.add(coin)?;
+            }
+            Ok(())
+        }
 
-            [project_creator_msg, contract_author_msg]
+        /// Shares every coin among the recipients in proportion to their weights.
+        /// The rounding remainder goes to the first recipient.
+        pub(crate) fn add_shared(
+            &mut self,
+            recipients: &[FeeCollector],
+            coins: impl IntoIterator<Item = Coin>,
+        ) -> StdResult<()> {
+            let total_weight: u128 = recipients.iter().map(|r| u128::from(r.weight)).sum();
+            for coin in coins {
+                let mut remainder = coin.amount;
+                let mut shares = Vec::with_capacity(recipients.len());
+                for recipient in recipients {
+                    let share = coin
+                        .amount
+                        .multiply_ratio(recipient.weight, Uint128::new(total_weight));
+                    remainder -= share;
+                    shares.push(share);
+                }
+                shares[0] += remainder;
+                for (recipient, share) in recipients.iter().zip(shares) {
+                    self.add(&recipient.address, [Coin::new(share, coin.denom.clone())])?;
+                }
+            }
+            Ok(())
+        }
+
+        pub(crate) fn into_vec(self) -> Vec<Payout> {
+            self.0
                 .into_iter()
-                .filter(|msg| matches!(msg, BankMsg::Send { amount, .. } if !amount.is_empty()))
+                .filter(|(_, coins)| !coins.is_empty())
+                .map(|(recipient, coins)| Payout {
+                    recipient,
+                    amount: coins.into_vec(),
+                })
+                .collect()
         }
 
-        pub(super) fn into_response(
-            self,
-            project_creator: Addr,
-            contract_author: Addr,
-        ) -> cosmwasm_std::Response {
-            let mut resp = cosmwasm_std::Response::new();
-            for bank_msg in self.into_bank_message_iter(project_creator, contract_author) {
-                resp = resp.add_message(bank_msg);
+        pub(crate) fn into_response(self) -> Response {
+            let mut resp = Response::new();
+            for Payout { recipient, amount } in self.into_vec() {
+                resp = resp.add_message(BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount,
+                });
             }
             resp
         }
     }
 }
 
-fn record_donation(deps: &mut DepsMut, info: &MessageInfo, project_id: u128) -> ContractResult<()> {
+/// Updates the contract-wide statistics with a settled donation.
+fn record_stats(deps: &mut DepsMut, patron: &Addr, splits: &[CoinSplit]) -> ContractResult<()> {
+    if !PATRONS.has(deps.storage, patron) {
+        PATRONS.save(deps.storage, patron, &())?;
+        PATRON_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+    }
+
+    for split in splits {
+        let denom = split.gross.denom.as_str();
+        let mut stats = DENOM_STATS
+            .may_load(deps.storage, denom)?
+            .unwrap_or_else(|| DenomStats::new(denom.to_string()));
+        stats.gross += split.gross.amount;
+        stats.fees += split.fee.amount;
+        stats.net += split.net.amount;
+        DENOM_STATS.save(deps.storage, denom, &stats)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the referrer's share of the fees to its earnings.
+fn record_referral(
+    deps: &mut DepsMut,
+    referrer: &Addr,
+    splits: &[CoinSplit],
+) -> ContractResult<()> {
+    for referral in splits.iter().filter_map(|split| split.referral.as_ref()) {
+        REFERRAL_EARNINGS.update(
+            deps.storage,
+            (referrer, &referral.denom),
+            |earnings| -> StdResult<_> { Ok(earnings.unwrap_or_default() + referral.amount) },
+        )?;
+    }
+    Ok(())
+}
+
+/// Records the donation. Anonymous donations (`patron` is `None`) only count towards the project totals.
+fn record_donation(
+    deps: &mut DepsMut,
+    env: &Env,
+    patron: Option<&Addr>,
+    project_id: u128,
+    funds: &[Coin],
+    memo: Option<String>,
+) -> ContractResult<()> {
+    let day_index = env.block.time.seconds() / SECONDS_PER_DAY;
+    for coin in funds {
+        PROJECT_TOTALS.update(
+            deps.storage,
+            (project_id, &coin.denom),
+            |total| -> StdResult<_> {
+                let mut total = total.unwrap_or_else(|| Coin::new(0u128, coin.denom.clone()));
+                total.amount += coin.amount;
+                Ok(total)
+            },
+        )?;
+        DONATION_BUCKETS.update(
+            deps.storage,
+            (project_id, &coin.denom, day_index),
+            |total| -> StdResult<_> { Ok(total.unwrap_or_default() + coin.amount) },
+        )?;
+    }
+
+    let Some(patron) = patron else {
+        return Ok(());
+    };
+
     let mut donations = DONATIONS
-        .may_load(deps.storage, (project_id, info.sender.clone()))?
+        .may_load(deps.storage, (project_id, patron.clone()))?
         .unwrap_or_default();
 
-    donations.push(DonationTx(info.funds.clone()));
-    DONATIONS.save(deps.storage, (project_id, info.sender.clone()), &donations)?;
+    donations.push(DonationTx::new(funds.to_vec(), memo));
+    DONATIONS.save(deps.storage, (project_id, patron.clone()), &donations)?;
+
+    for coin in funds {
+        let key = (project_id, coin.denom.as_str(), patron);
+        let old_total = PATRON_TOTALS.may_load(deps.storage, key)?;
+        let new_total = old_total.unwrap_or_default() + coin.amount;
+        PATRON_TOTALS.save(deps.storage, key, &new_total)?;
+
+        let prefix = (project_id, coin.denom.as_str());
+        if let Some(old_total) = old_total {
+            TOP_DONORS.remove(deps.storage, (prefix, old_total.u128(), patron));
+        }
+        TOP_DONORS.save(deps.storage, (prefix, new_total.u128(), patron), &())?;
+    }
 
     Ok(())
 }
 
-pub fn donate(deps: &mut DepsMut, info: MessageInfo, project_id: u128) -> ContractResult<Response> {
-    let project = PROJECTS.load(deps.storage, project_id)?;
+/// Adds the donation to the funder's tallies in every active round the project is registered in.
+fn record_round_contributions(
+    deps: &mut DepsMut,
+    env: &Env,
+    patron: &Addr,
+    project_id: u128,
+    funds: &[Coin],
+) -> ContractResult<()> {
+    let round_ids: Vec<u128> = PROJECT_ROUNDS
+        .prefix(project_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    for round_id in round_ids {
+        let round = ROUNDS.load(deps.storage, round_id)?;
+        // A round that is over cannot count any more donations, so the project leaves it for good.
+        if round.finalized || env.block.time >= round.end {
+            PROJECT_ROUNDS.remove(deps.storage, (project_id, round_id));
+            continue;
+        }
+        if !round.is_active(env.block.time) {
+            continue;
+        }
+        let amount: Uint128 = funds
+            .iter()
+            .filter(|coin| coin.denom == round.denom)
+            .map(|coin| coin.amount)
+            .sum();
+        if amount.is_zero() {
+            continue;
+        }
+        let key = (round_id, project_id, patron.clone());
+        let old = ROUND_CONTRIBUTIONS
+            .may_load(deps.storage, key.clone())?
+            .unwrap_or_default();
+        let new = old + amount;
+        ROUND_CONTRIBUTIONS.save(deps.storage, key, &new)?;
+
+        // Any Uint128 fits into Decimal256, so this cannot overflow.
+        let old = Decimal256::from_ratio(old, 1u128);
+        let new = Decimal256::from_ratio(new, 1u128);
+        let mut tally = ROUND_TALLIES
+            .may_load(deps.storage, (round_id, project_id))?
+            .unwrap_or_default();
+        tally.sum += new - old;
+        // The old square root was added exactly like this before, so subtracting it cannot underflow.
+        tally.sum_of_sqrts = tally.sum_of_sqrts + new.sqrt() - old.sqrt();
+        ROUND_TALLIES.save(deps.storage, (round_id, project_id), &tally)?;
+    }
+
+    Ok(())
+}
+
+fn donation_event(
+    project_id: u128,
+    sender: &Addr,
+    patron: Option<&Addr>,
+    memo: Option<&str>,
+    referrer: Option<&Addr>,
+    splits: &[CoinSplit],
+) -> Event {
+    let mut event = Event::new("donation")
+        .add_attribute("project_id", project_id.to_string())
+        .add_attribute("sender", sender);
+    // The rate of every donated coin, in the order of the funds. Attributes cannot be empty,
+    // so a donation without funds has none.
+    if !splits.is_empty() {
+        let fee_rates = splits
+            .iter()
+            .map(|split| split.tier.fee_rate().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        event = event.add_attribute("fee_rate", fee_rates);
+    }
+    event = match patron {
+        Some(patron) => event.add_attribute("patron", patron),
+        None => event.add_attribute("anonymous", "true"),
+    };
+    if let Some(memo) = memo {
+        event = event.add_attribute("memo", memo);
+    }
+    if let Some(referrer) = referrer {
+        event = event.add_attribute("referrer", referrer);
+    }
+    event
+}
+
+/// Returns the patron credited with the donation: `on_behalf_of` if set, no one for anonymous
+/// donations and the sender otherwise.
+pub(crate) fn validate_patron(
+    api: &dyn Api,
+    sender: Option<&Addr>,
+    on_behalf_of: Option<String>,
+    anonymous: bool,
+) -> ContractResult<Option<Addr>> {
+    let patron = match (on_behalf_of, anonymous) {
+        (Some(_), true) => return Err(ContractError::AnonymousOnBehalfOf),
+        (Some(patron), false) => Some(api.addr_validate(&patron)?),
+        (None, false) => sender.cloned(),
+        (None, true) => None,
+    };
+    Ok(patron)
+}
+
+/// Validates the referrer, which cannot be any of the donors.
+pub(crate) fn validate_referrer(
+    api: &dyn Api,
+    referrer: Option<String>,
+    donors: &[Option<&Addr>],
+) -> ContractResult<Option<Addr>> {
+    let referrer = referrer
+        .map(|referrer| api.addr_validate(&referrer))
+        .transpose()?;
+    if referrer.is_some() && donors.contains(&referrer.as_ref()) {
+        return Err(ContractError::SelfReferral);
+    }
+    Ok(referrer)
+}
+
+/// The optional fields of `ExecuteMsg::Donate`.
+#[derive(Default)]
+pub struct DonateOptions {
+    pub on_behalf_of: Option<String>,
+    pub anonymous: bool,
+    pub memo: Option<String>,
+    pub referrer: Option<String>,
+    /// The amounts the project receives in full when the patron pays the fee on top.
+    pub cover_fee: Option<Vec<Coin>>,
+}
+
+/// Donates the attached funds to the project.
+///
+/// If `on_behalf_of` is set, the donation is credited to that address instead of the sender.
+/// Anonymous donations are not credited to anyone. If the patron covers the fee,
+/// what the split leaves over is refunded to the sender.
+pub fn donate(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: MessageInfo,
+    project_id: u128,
+    options: DonateOptions,
+) -> ContractResult<Response> {
+    let DonateOptions {
+        on_behalf_of,
+        anonymous,
+        memo,
+        referrer,
+        cover_fee,
+    } = options;
+    let project = load_project(deps.storage, project_id)?;
 
-    record_donation(deps, &info, project_id)?;
+    if memo.as_ref().is_some_and(|memo| memo.len() > MAX_MEMO_LEN) {
+        return Err(ContractError::MemoTooLong(MAX_MEMO_LEN));
+    }
+    if memo.as_ref().is_some_and(|memo| memo.trim().is_empty()) {
+        return Err(ContractError::EmptyMemo);
+    }
 
-    let contract_author = AUTHOR.load(deps.storage)?;
+    let patron = validate_patron(deps.api, Some(&info.sender), on_behalf_of, anonymous)?;
+    let referrer = validate_referrer(deps.api, referrer, &[Some(&info.sender), patron.as_ref()])?;
 
-    let resp =
-        split_by_recipient(info.funds).into_response(project.creator.clone(), contract_author);
+    let mut payouts = Payouts::default();
+    let splits = settle_donation(
+        deps.storage,
+        project_id,
+        &project,
+        &info.funds,
+        referrer.as_ref(),
+        cover_fee.as_deref(),
+        &mut payouts,
+    )?;
+    payouts.add(&info.sender, refunds(&info.funds, &splits))?;
 
+    let donated: Vec<Coin> = splits.iter().map(|split| split.gross.clone()).collect();
+    record_donation(
+        deps,
+        env,
+        patron.as_ref(),
+        project_id,
+        &donated,
+        memo.clone(),
+    )?;
+    // Rounds are matched per funder, so that splitting a donation over made-up patrons gains nothing.
+    if patron.is_some() {
+        record_round_contributions(deps, env, &info.sender, project_id, &donated)?;
+    }
+    record_stats(deps, patron.as_ref().unwrap_or(&info.sender), &splits)?;
+    if let Some(referrer) = &referrer {
+        record_referral(deps, referrer, &splits)?;
+    }
+
+    let resp = payouts.into_response().add_event(donation_event(
+        project_id,
+        &info.sender,
+        patron.as_ref(),
+        memo.as_deref(),
+        referrer.as_ref(),
+        &splits,
+    ));
     Ok(resp)
 }
 
+pub fn donate_many(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: MessageInfo,
+    allocations: Vec<(u128, Vec<Coin>)>,
+) -> ContractResult<Response> {
+    let mut allocated = Coins::default();
+    for coin in allocations.iter().flat_map(|(_, funds)| funds) {
+        allocated.add(coin.clone())?;
+    }
+    if allocated != Coins::try_from(info.funds.as_slice())? {
+        return Err(ContractError::AllocationsMismatch);
+    }
+
+    let mut payouts = Payouts::default();
+    let mut events = Vec::with_capacity(allocations.len());
+    for (project_id, funds) in allocations {
+        let project = load_project(deps.storage, project_id)?;
+        record_donation(deps, env, Some(&info.sender), project_id, &funds, None)?;
+        record_round_contributions(deps, env, &info.sender, project_id, &funds)?;
+        let splits = settle_donation(
+            deps.storage,
+            project_id,
+            &project,
+            &funds,
+            None,
+            None,
+            &mut payouts,
+        )?;
+        record_stats(deps, &info.sender, &splits)?;
+        events.push(donation_event(
+            project_id,
+            &info.sender,
+            Some(&info.sender),
+            None,
+            None,
+            &splits,
+        ));
+    }
+
+    Ok(payouts.into_response().add_events(events))
+}
+
+pub fn create_round(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    name: String,
+    start: Timestamp,
+    end: Timestamp,
+) -> ContractResult<u128> {
+    let matching_pool = cw_utils::one_coin(info)?;
+    if start >= end || end <= env.block.time {
+        return Err(ContractError::InvalidRoundWindow);
+    }
+
+    let round_count = ROUND_COUNT.load(deps.storage)?;
+    ROUND_COUNT.save(deps.storage, &(round_count + 1))?;
+
+    let round = Round {
+        name,
+        operator: info.sender.clone(),
+        denom: matching_pool.denom,
+        matching_pool: matching_pool.amount,
+        start,
+        end,
+        finalized: false,
+    };
+    ROUNDS.save(deps.storage, round_count, &round)?;
+
+    Ok(round_count)
+}
+
+pub fn register_round_project(
+    deps: &mut DepsMut,
+    env: &Env,
+    sender: &Addr,
+    round_id: u128,
+    project_id: u128,
+) -> ContractResult<()> {
+    let round = load_round(deps.storage, round_id)?;
+    if *sender != round.operator {
+        return Err(ContractError::Unauthorized);
+    }
+    if round.finalized || env.block.time >= round.end {
+        return Err(ContractError::RoundEnded(round_id));
+    }
+    load_project(deps.storage, project_id)?;
+
+    ROUND_INVITATIONS.save(deps.storage, (round_id, project_id), &())?;
+
+    Ok(())
+}
+
+pub fn join_round(
+    deps: &mut DepsMut,
+    env: &Env,
+    sender: &Addr,
+    round_id: u128,
+    project_id: u128,
+) -> ContractResult<()> {
+    let project = load_project(deps.storage, project_id)?;
+    assert_project_role(
+        deps.storage,
+        project_id,
+        &project,
+        sender,
+        ProjectRole::StatusManager,
+    )?;
+    let round = load_round(deps.storage, round_id)?;
+    if round.finalized || env.block.time >= round.end {
+        return Err(ContractError::RoundEnded(round_id));
+    }
+    if !ROUND_INVITATIONS.has(deps.storage, (round_id, project_id)) {
+        return Err(ContractError::NotInvitedToRound(round_id, project_id));
+    }
+    let project_count = ROUND_PROJECTS
+        .prefix(round_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_ROUND_PROJECTS)
+        .count();
+    if project_count == MAX_ROUND_PROJECTS {
+        return Err(ContractError::TooManyRoundProjects(MAX_ROUND_PROJECTS));
+    }
+    ROUND_INVITATIONS.remove(deps.storage, (round_id, project_id));
+
+    ROUND_PROJECTS.save(deps.storage, (round_id, project_id), &())?;
+    PROJECT_ROUNDS.save(deps.storage, (project_id, round_id), &())?;
+
+    Ok(())
+}
+
+pub fn propose_project_owner(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    new_owner: String,
+) -> ContractResult<Response> {
+    let project = load_project(deps.storage, project_id)?;
+    if *sender != project.creator {
+        return Err(ContractError::Unauthorized);
+    }
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    PENDING_PROJECT_OWNERS.save(deps.storage, project_id, &new_owner)?;
+
+    let event = Event::new("propose_project_owner")
+        .add_attribute("project_id", project_id.to_string())
+        .add_attribute("owner", sender)
+        .add_attribute("pending_owner", new_owner);
+    Ok(Response::new().add_event(event))
+}
+
+pub fn accept_project_ownership(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+) -> ContractResult<Response> {
+    let mut project = load_project(deps.storage, project_id)?;
+    let pending_owner = PENDING_PROJECT_OWNERS
+        .may_load(deps.storage, project_id)?
+        .ok_or(ContractError::NoPendingOwner(project_id))?;
+    if *sender != pending_owner {
+        return Err(ContractError::Unauthorized);
+    }
+    // Taking over an active project counts towards the new owner's cap just like creating one.
+    if project.status == ProjectStatus::Active {
+        let config = CONFIG.load(deps.storage)?;
+        assert_active_projects_below_cap(deps.storage, &config, &pending_owner)?;
+    }
+    PENDING_PROJECT_OWNERS.remove(deps.storage, project_id);
+
+    // The managers and the beneficiary were chosen by the previous owner, so they do not carry over.
+    let managers: Vec<Addr> = PROJECT_MANAGERS
+        .prefix(project_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for manager in &managers {
+        PROJECT_MANAGERS.remove(deps.storage, (project_id, manager));
+    }
+    project.beneficiary = None;
+
+    let previous_owner = std::mem::replace(&mut project.creator, pending_owner);
+    PROJECTS.save(deps.storage, project_id, &project)?;
+
+    let event = Event::new("accept_project_ownership")
+        .add_attribute("project_id", project_id.to_string())
+        .add_attribute("previous_owner", previous_owner)
+        .add_attribute("owner", sender);
+    Ok(Response::new().add_event(event))
+}
+
+/// Checks that the sender is the project creator or a manager with the role.
+fn assert_project_role(
+    storage: &dyn Storage,
+    project_id: u128,
+    project: &Project,
+    sender: &Addr,
+    role: ProjectRole,
+) -> ContractResult<()> {
+    if *sender == project.creator {
+        return Ok(());
+    }
+    let roles = PROJECT_MANAGERS
+        .may_load(storage, (project_id, sender))?
+        .unwrap_or_default();
+    if !roles.contains(&role) {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+pub fn set_project_manager(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    manager: String,
+    roles: Vec<ProjectRole>,
+) -> ContractResult<()> {
+    let project = load_project(deps.storage, project_id)?;
+    if *sender != project.creator {
+        return Err(ContractError::Unauthorized);
+    }
+    let manager = deps.api.addr_validate(&manager)?;
+    let roles = roles.into_iter().fold(vec![], |mut roles, role| {
+        if !roles.contains(&role) {
+            roles.push(role);
+        }
+        roles
+    });
+    if roles.is_empty() {
+        PROJECT_MANAGERS.remove(deps.storage, (project_id, &manager));
+    } else {
+        PROJECT_MANAGERS.save(deps.storage, (project_id, &manager), &roles)?;
+    }
+    Ok(())
+}
+
+pub fn update_project_metadata(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    name: Option<String>,
+    tags: Option<Vec<String>>,
+) -> ContractResult<()> {
+    let mut project = load_project(deps.storage, project_id)?;
+    assert_project_role(
+        deps.storage,
+        project_id,
+        &project,
+        sender,
+        ProjectRole::MetadataEditor,
+    )?;
+
+    unindex_project(deps.storage, project_id, &project)?;
+    if let Some(name) = name {
+        project.name = name;
+    }
+    if let Some(tags) = tags {
+        project.tags = normalize_tags(tags)?;
+    }
+    claim_slug(deps.storage, &project.name, project_id)?;
+    index_project(deps.storage, project_id, &project)?;
+    PROJECTS.save(deps.storage, project_id, &project)?;
+    Ok(())
+}
+
+pub fn set_project_beneficiary(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    beneficiary: Option<String>,
+) -> ContractResult<()> {
+    let mut project = load_project(deps.storage, project_id)?;
+    assert_project_role(
+        deps.storage,
+        project_id,
+        &project,
+        sender,
+        ProjectRole::BeneficiaryManager,
+    )?;
+    project.beneficiary = beneficiary
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    PROJECTS.save(deps.storage, project_id, &project)?;
+    Ok(())
+}
+
+pub fn set_project_status(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    status: ProjectStatus,
+) -> ContractResult<()> {
+    let mut project = load_project(deps.storage, project_id)?;
+    assert_project_role(
+        deps.storage,
+        project_id,
+        &project,
+        sender,
+        ProjectRole::StatusManager,
+    )?;
+    if project.status == status {
+        return Ok(());
+    }
+    // Reactivating counts towards the creator's cap just like creating a project.
+    if status == ProjectStatus::Active {
+        let config = CONFIG.load(deps.storage)?;
+        assert_active_projects_below_cap(deps.storage, &config, &project.creator)?;
+    }
+    project.status = status;
+    PROJECTS.save(deps.storage, project_id, &project)?;
+    Ok(())
+}
+
+/// Computes the quadratic funding weight `(sum of sqrt(c_i))^2 - sum of c_i` of every project in the round
+/// that is not frozen.
+///
+/// Subtracting the plain sum leaves only the part of the match that comes from the pool, so that
+/// a project with a single patron gets no matching at all.
+fn quadratic_weights(
+    storage: &dyn Storage,
+    round_id: u128,
+    project_ids: &[u128],
+) -> ContractResult<Vec<(u128, Uint256)>> {
+    let mut weights = Vec::with_capacity(project_ids.len());
+    for &project_id in project_ids {
+        // Frozen projects get no matching, their share goes to the other projects.
+        if FROZEN_PROJECTS.has(storage, project_id) {
+            continue;
+        }
+        let RoundTally { sum, sum_of_sqrts } = ROUND_TALLIES
+            .may_load(storage, (round_id, project_id))?
+            .unwrap_or_default();
+        let weight = (sum_of_sqrts * sum_of_sqrts).saturating_sub(sum);
+        weights.push((project_id, weight.atomics()));
+    }
+
+    Ok(weights)
+}
+
+pub fn finalize_round(deps: &mut DepsMut, env: &Env, round_id: u128) -> ContractResult<Response> {
+    let mut round = load_round(deps.storage, round_id)?;
+    if round.finalized {
+        return Err(ContractError::RoundFinalized(round_id));
+    }
+    if env.block.time < round.end {
+        return Err(ContractError::RoundNotEnded(round_id));
+    }
+
+    let project_ids: Vec<u128> = ROUND_PROJECTS
+        .prefix(round_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let weights = quadratic_weights(deps.storage, round_id, &project_ids)?;
+    let total_weight: Uint256 = weights.iter().map(|(_, weight)| *weight).sum();
+
+    let mut resp = Response::new()
+        .add_attribute("action", "finalize_round")
+        .add_attribute("round_id", round_id.to_string());
+    let mut distributed = Uint128::zero();
+    if !total_weight.is_zero() {
+        for (project_id, weight) in weights {
+            let amount: Uint128 = Uint256::from(round.matching_pool)
+                .mul_floor((weight, total_weight))
+                .try_into()
+                .map_err(cosmwasm_std::StdError::from)?;
+            if amount.is_zero() {
+                continue;
+            }
+            let project = PROJECTS.load(deps.storage, project_id)?;
+            distributed += amount;
+            resp = resp.add_message(BankMsg::Send {
+                to_address: project.recipient().to_string(),
+                amount: vec![Coin::new(amount, round.denom.clone())],
+            });
+        }
+    }
+
+    // Whatever is left due to rounding (or the whole pool if nobody contributed) goes back to the operator.
+    let remainder = round.matching_pool - distributed;
+    if !remainder.is_zero() {
+        resp = resp.add_message(BankMsg::Send {
+            to_address: round.operator.to_string(),
+            amount: vec![Coin::new(remainder, round.denom.clone())],
+        });
+    }
+
+    round.finalized = true;
+    ROUNDS.save(deps.storage, round_id, &round)?;
+    for project_id in project_ids {
+        PROJECT_ROUNDS.remove(deps.storage, (project_id, round_id));
+    }
+
+    Ok(resp)
+}
+
+fn assert_admin(storage: &dyn Storage, sender: &Addr) -> ContractResult<()> {
+    if OWNERSHIP.load(storage)?.owner.as_ref() != Some(sender) {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+pub fn transfer_ownership(
+    deps: &mut DepsMut,
+    env: &Env,
+    sender: &Addr,
+    new_owner: String,
+    expiry: Option<Expiration>,
+) -> ContractResult<()> {
+    assert_admin(deps.storage, sender)?;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    if expiry.is_some_and(|expiry| expiry.is_expired(&env.block)) {
+        return Err(ContractError::OwnershipTransferExpired);
+    }
+    OWNERSHIP.update(deps.storage, |mut ownership| -> StdResult<_> {
+        ownership.pending_owner = Some(new_owner);
+        ownership.pending_expiry = expiry;
+        Ok(ownership)
+    })?;
+    Ok(())
+}
+
+pub fn accept_ownership(deps: &mut DepsMut, env: &Env, sender: &Addr) -> ContractResult<()> {
+    let mut ownership = OWNERSHIP.load(deps.storage)?;
+    let pending_owner = ownership
+        .pending_owner
+        .take()
+        .ok_or(ContractError::NoPendingOwnership)?;
+    if *sender != pending_owner {
+        return Err(ContractError::Unauthorized);
+    }
+    if ownership
+        .pending_expiry
+        .take()
+        .is_some_and(|expiry| expiry.is_expired(&env.block))
+    {
+        return Err(ContractError::OwnershipTransferExpired);
+    }
+    ownership.owner = Some(pending_owner);
+    OWNERSHIP.save(deps.storage, &ownership)?;
+    Ok(())
+}
+
+pub fn renounce_ownership(deps: &mut DepsMut, sender: &Addr) -> ContractResult<()> {
+    assert_admin(deps.storage, sender)?;
+    let ownership = Ownership {
+        owner: None,
+        pending_owner: None,
+        pending_expiry: None,
+    };
+    OWNERSHIP.save(deps.storage, &ownership)?;
+    Ok(())
+}
+
+fn assert_not_paused(storage: &dyn Storage) -> ContractResult<()> {
+    if CONFIG.load(storage)?.paused {
+        return Err(ContractError::Paused);
+    }
+    Ok(())
+}
+
+/// Pauses or unpauses the contract. Only the contract owner and the guardian can do this.
+pub fn set_paused(deps: &mut DepsMut, sender: &Addr, paused: bool) -> ContractResult<()> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.guardian.as_ref() != Some(sender) {
+        assert_admin(deps.storage, sender)?;
+    }
+    config.paused = paused;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(())
+}
+
+pub fn verify_project(
+    deps: &mut DepsMut,
+    env: &Env,
+    sender: &Addr,
+    project_id: u128,
+    attestation_uri: Option<String>,
+) -> ContractResult<Response> {
+    assert_admin(deps.storage, sender)?;
+    load_project(deps.storage, project_id)?;
+    if let Some(uri) = &attestation_uri {
+        validate_text(uri)?;
+    }
+
+    let mut event =
+        Event::new("verify_project").add_attribute("project_id", project_id.to_string());
+    if let Some(uri) = &attestation_uri {
+        event = event.add_attribute("attestation_uri", uri);
+    }
+    let verification = Verification {
+        verified_by: sender.clone(),
+        verified_at: env.block.time,
+        attestation_uri,
+    };
+    VERIFICATIONS.save(deps.storage, project_id, &verification)?;
+    Ok(Response::new().add_event(event))
+}
+
+pub fn revoke_verification(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+) -> ContractResult<Response> {
+    assert_admin(deps.storage, sender)?;
+    if !VERIFICATIONS.has(deps.storage, project_id) {
+        return Err(ContractError::ProjectNotVerified(project_id));
+    }
+    VERIFICATIONS.remove(deps.storage, project_id);
+
+    let event =
+        Event::new("revoke_verification").add_attribute("project_id", project_id.to_string());
+    Ok(Response::new().add_event(event))
+}
+
+/// Sets the fee rate that replaces the global tiers for the project, or removes it.
+pub fn set_fee_override(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    fee_rate: Option<Decimal>,
+) -> ContractResult<()> {
+    assert_admin(deps.storage, sender)?;
+    load_project(deps.storage, project_id)?;
+    match fee_rate {
+        Some(fee_rate) if fee_rate > Decimal::one() => {
+            return Err(ContractError::InvalidFeeRate(fee_rate))
+        }
+        Some(fee_rate) => FEE_OVERRIDES.save(deps.storage, project_id, &fee_rate)?,
+        None => FEE_OVERRIDES.remove(deps.storage, project_id),
+    }
+    Ok(())
+}
+
+fn assert_moderator(storage: &dyn Storage, sender: &Addr) -> ContractResult<()> {
+    if MODERATORS.has(storage, sender) {
+        return Ok(());
+    }
+    assert_admin(storage, sender)
+}
+
+/// Checks that the moderation text is not empty and at most `MAX_MEMO_LEN` bytes long.
+fn validate_text(text: &str) -> ContractResult<()> {
+    if text.trim().is_empty() || text.len() > MAX_MEMO_LEN {
+        return Err(ContractError::InvalidText(MAX_MEMO_LEN));
+    }
+    Ok(())
+}
+
+pub fn update_moderators(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> ContractResult<()> {
+    assert_admin(deps.storage, sender)?;
+    for moderator in add {
+        let moderator = deps.api.addr_validate(&moderator)?;
+        MODERATORS.save(deps.storage, &moderator, &())?;
+    }
+    for moderator in remove {
+        let moderator = deps.api.addr_validate(&moderator)?;
+        MODERATORS.remove(deps.storage, &moderator);
+    }
+    Ok(())
+}
+
+pub fn freeze_project(
+    deps: &mut DepsMut,
+    env: &Env,
+    sender: &Addr,
+    project_id: u128,
+    reason: String,
+) -> ContractResult<Response> {
+    assert_moderator(deps.storage, sender)?;
+    load_project(deps.storage, project_id)?;
+    validate_text(&reason)?;
+    // Freezing again would drop the pending appeal and the record of who froze the project.
+    if FROZEN_PROJECTS.has(deps.storage, project_id) {
+        return Err(ContractError::ProjectFrozen(project_id));
+    }
+
+    let event = Event::new("freeze_project")
+        .add_attribute("project_id", project_id.to_string())
+        .add_attribute("moderator", sender)
+        .add_attribute("reason", &reason);
+    let freeze = Freeze {
+        reason,
+        frozen_by: sender.clone(),
+        frozen_at: env.block.time,
+        appeal: None,
+    };
+    FROZEN_PROJECTS.save(deps.storage, project_id, &freeze)?;
+    Ok(Response::new().add_event(event))
+}
+
+pub fn appeal_freeze(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+    statement: String,
+) -> ContractResult<Response> {
+    let project = load_project(deps.storage, project_id)?;
+    assert_project_role(
+        deps.storage,
+        project_id,
+        &project,
+        sender,
+        ProjectRole::StatusManager,
+    )?;
+    let mut freeze = FROZEN_PROJECTS
+        .may_load(deps.storage, project_id)?
+        .ok_or(ContractError::ProjectNotFrozen(project_id))?;
+    validate_text(&statement)?;
+
+    let event = Event::new("appeal_freeze")
+        .add_attribute("project_id", project_id.to_string())
+        .add_attribute("sender", sender)
+        .add_attribute("statement", &statement);
+    freeze.appeal = Some(statement);
+    FROZEN_PROJECTS.save(deps.storage, project_id, &freeze)?;
+    Ok(Response::new().add_event(event))
+}
+
+pub fn unfreeze_project(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    project_id: u128,
+) -> ContractResult<Response> {
+    assert_moderator(deps.storage, sender)?;
+    if !FROZEN_PROJECTS.has(deps.storage, project_id) {
+        return Err(ContractError::ProjectNotFrozen(project_id));
+    }
+    FROZEN_PROJECTS.remove(deps.storage, project_id);
+
+    let event = Event::new("unfreeze_project")
+        .add_attribute("project_id", project_id.to_string())
+        .add_attribute("moderator", sender);
+    Ok(Response::new().add_event(event))
+}
+
+fn validate_fee_collectors(
+    deps: &DepsMut,
+    fee_collectors: Vec<(String, u32)>,
+) -> ContractResult<Vec<FeeCollector>> {
+    if fee_collectors.is_empty() {
+        return Err(ContractError::InvalidFeeCollectors);
+    }
+    let mut validated: Vec<FeeCollector> = Vec::with_capacity(fee_collectors.len());
+    for (address, weight) in fee_collectors {
+        let address = deps.api.addr_validate(&address)?;
+        if weight == 0 || validated.iter().any(|c| c.address == address) {
+            return Err(ContractError::InvalidFeeCollectors);
+        }
+        validated.push(FeeCollector { address, weight });
+    }
+    Ok(validated)
+}
+
+pub fn update_config(
+    deps: &mut DepsMut,
+    sender: &Addr,
+    update: ConfigUpdate,
+) -> ContractResult<()> {
+    assert_admin(deps.storage, sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(unique_project_names) = update.unique_project_names {
+        config.unique_project_names = unique_project_names;
+    }
+    if let Some(fee) = update.project_creation_fee {
+        config.project_creation_fee = Some(fee).filter(|fee| !fee.amount.is_zero());
+    }
+    if let Some(max) = update.max_active_projects_per_creator {
+        config.max_active_projects_per_creator = Some(max).filter(|max| *max != 0);
+    }
+    if let Some(fee_collectors) = update.fee_collectors {
+        config.fee_collectors = validate_fee_collectors(deps, fee_collectors)?;
+    }
+    if let Some(referral_share) = update.referral_share {
+        if referral_share > Decimal::one() {
+            return Err(ContractError::InvalidFeeRate(referral_share));
+        }
+        config.referral_share = referral_share;
+    }
+    if let Some(guardian) = update.guardian {
+        config.guardian = guardian
+            .map(|guardian| deps.api.addr_validate(&guardian))
+            .transpose()?;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    Ok(())
+}
+
 pub fn execute(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> ContractResult<Response> {
     let resp = match msg {
-        ExecuteMsg::CreateProject { name } => {
-            let creator = info.sender;
-            create_project(&mut deps, name, creator)?;
+        ExecuteMsg::CreateProject { name, tags } => create_project(&mut deps, &info, name, tags)?,
+        ExecuteMsg::Donate {
+            project_id,
+            on_behalf_of,
+            anonymous,
+            memo,
+            referrer,
+            cover_fee,
+        } => {
+            let options = DonateOptions {
+                on_behalf_of,
+                anonymous,
+                memo,
+                referrer,
+                cover_fee,
+            };
+            donate(&mut deps, &env, info, project_id, options)?
+        }
+        ExecuteMsg::DonateMany { allocations } => donate_many(&mut deps, &env, info, allocations)?,
+        ExecuteMsg::CreateRound { name, start, end } => {
+            let round_id = create_round(&mut deps, &env, &info, name, start, end)?;
             Response::new()
+                .add_attribute("action", "create_round")
+                .add_attribute("round_id", round_id.to_string())
+        }
+        ExecuteMsg::RegisterRoundProject {
+            round_id,
+            project_id,
+        } => {
+            register_round_project(&mut deps, &env, &info.sender, round_id, project_id)?;
+            Response::new()
+        }
+        ExecuteMsg::JoinRound {
+            round_id,
+            project_id,
+        } => {
+            join_round(&mut deps, &env, &info.sender, round_id, project_id)?;
+            Response::new()
+        }
+        ExecuteMsg::UpdateConfig(update) => {
+            update_config(&mut deps, &info.sender, update)?;
+            Response::new().add_attribute("action", "update_config")
+        }
+        ExecuteMsg::ProposeProjectOwner {
+            project_id,
+            new_owner,
+        } => propose_project_owner(&mut deps, &info.sender, project_id, new_owner)?,
+        ExecuteMsg::AcceptProjectOwnership { project_id } => {
+            accept_project_ownership(&mut deps, &info.sender, project_id)?
+        }
+        ExecuteMsg::SetProjectManager {
+            project_id,
+            manager,
+            roles,
+        } => {
+            set_project_manager(&mut deps, &info.sender, project_id, manager, roles)?;
+            Response::new().add_attribute("action", "set_project_manager")
+        }
+        ExecuteMsg::UpdateProjectMetadata {
+            project_id,
+            name,
+            tags,
+        } => {
+            update_project_metadata(&mut deps, &info.sender, project_id, name, tags)?;
+            Response::new().add_attribute("action", "update_project_metadata")
+        }
+        ExecuteMsg::SetProjectBeneficiary {
+            project_id,
+            beneficiary,
+        } => {
+            set_project_beneficiary(&mut deps, &info.sender, project_id, beneficiary)?;
+            Response::new().add_attribute("action", "set_project_beneficiary")
+        }
+        ExecuteMsg::SetProjectStatus { project_id, status } => {
+            set_project_status(&mut deps, &info.sender, project_id, status)?;
+            Response::new().add_attribute("action", "set_project_status")
+        }
+        ExecuteMsg::TransferOwnership { new_owner, expiry } => {
+            transfer_ownership(&mut deps, &env, &info.sender, new_owner, expiry)?;
+            Response::new().add_attribute("action", "transfer_ownership")
+        }
+        ExecuteMsg::AcceptOwnership {} => {
+            accept_ownership(&mut deps, &env, &info.sender)?;
+            Response::new().add_attribute("action", "accept_ownership")
+        }
+        ExecuteMsg::RenounceOwnership {} => {
+            renounce_ownership(&mut deps, &info.sender)?;
+            Response::new().add_attribute("action", "renounce_ownership")
+        }
+        ExecuteMsg::Pause {} => {
+            set_paused(&mut deps, &info.sender, true)?;
+            Response::new().add_attribute("action", "pause")
+        }
+        ExecuteMsg::Unpause {} => {
+            set_paused(&mut deps, &info.sender, false)?;
+            Response::new().add_attribute("action", "unpause")
+        }
+        ExecuteMsg::VerifyProject {
+            project_id,
+            attestation_uri,
+        } => verify_project(&mut deps, &env, &info.sender, project_id, attestation_uri)?,
+        ExecuteMsg::RevokeVerification { project_id } => {
+            revoke_verification(&mut deps, &info.sender, project_id)?
+        }
+        ExecuteMsg::SetFeeOverride {
+            project_id,
+            fee_rate,
+        } => {
+            set_fee_override(&mut deps, &info.sender, project_id, fee_rate)?;
+            Response::new().add_attribute("action", "set_fee_override")
+        }
+        ExecuteMsg::UpdateModerators { add, remove } => {
+            update_moderators(&mut deps, &info.sender, add, remove)?;
+            Response::new().add_attribute("action", "update_moderators")
+        }
+        ExecuteMsg::FreezeProject { project_id, reason } => {
+            freeze_project(&mut deps, &env, &info.sender, project_id, reason)?
+        }
+        ExecuteMsg::AppealFreeze {
+            project_id,
+            statement,
+        } => appeal_freeze(&mut deps, &info.sender, project_id, statement)?,
+        ExecuteMsg::UnfreezeProject { project_id } => {
+            unfreeze_project(&mut deps, &info.sender, project_id)?
         }
-        ExecuteMsg::Donate { project_id } => donate(&mut deps, info, project_id)?,
+        ExecuteMsg::FinalizeRound { round_id } => finalize_round(&mut deps, &env, round_id)?,
     };
     Ok(resp)
 }
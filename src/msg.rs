@@ -1,9 +1,39 @@
-use crate::state::{DonationTx, Project};
+use crate::state::{
+    Config, DenomStats, DonationTx, Freeze, Ownership, Project, ProjectRole, ProjectStatus, Round,
+    Verification,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
+use cw_utils::Expiration;
+use serde::{Deserialize, Deserializer};
+
+#[cw_serde]
+pub struct ProjectResp {
+    pub id: u128,
+    pub project: Project,
+    /// The amounts donated to the project per denom, before the fees are deducted.
+    pub totals: Vec<Coin>,
+    /// Set if the contract owner verified the project.
+    pub verification: Option<Verification>,
+    /// The fee rate that replaces the global tiers for the project, if any.
+    pub fee_override: Option<Decimal>,
+    /// Set while a moderator has the project frozen.
+    pub freeze: Option<Freeze>,
+}
 
 #[cw_serde]
 pub struct ListProjectsResp {
-    pub projects: Vec<Project>,
+    pub projects: Vec<ProjectResp>,
+}
+
+#[cw_serde]
+pub struct GetProjectsResp {
+    pub projects: Vec<ProjectResp>,
+}
+
+#[cw_serde]
+pub struct ListProjectsByCreatorResp {
+    pub projects: Vec<ProjectResp>,
 }
 
 #[cw_serde]
@@ -11,17 +41,410 @@ pub struct ListDonationsForProjectByPatronResp {
     pub donations: Vec<DonationTx>,
 }
 
+#[cw_serde]
+pub struct ProjectTotalsResp {
+    pub totals: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct Donor {
+    pub patron: Addr,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct TopDonorsResp {
+    pub donors: Vec<Donor>,
+}
+
+#[cw_serde]
+pub struct ProjectRaised {
+    pub project_id: u128,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct TopProjectsResp {
+    pub projects: Vec<ProjectRaised>,
+}
+
+#[cw_serde]
+pub enum Granularity {
+    Day,
+    /// Weeks are counted from the Unix epoch, so they start on Thursdays.
+    Week,
+}
+
+#[cw_serde]
+pub struct HistoryBucket {
+    /// The start of the day or week.
+    pub start: Timestamp,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct DonationHistoryResp {
+    /// Only the buckets with donations are listed, oldest first.
+    pub buckets: Vec<HistoryBucket>,
+}
+
+#[cw_serde]
+pub enum FeeTier {
+    /// 10% fee for donations of at most 10,000 tokens of a denom.
+    BelowThreshold,
+    /// 5% fee for donations of more than 10,000 tokens of a denom.
+    AboveThreshold,
+    /// The fee rate the contract owner set for the project, whatever the amount.
+    Override(Decimal),
+}
+
+impl FeeTier {
+    pub fn fee_rate(&self) -> Decimal {
+        match self {
+            FeeTier::BelowThreshold => Decimal::percent(10),
+            FeeTier::AboveThreshold => Decimal::percent(5),
+            FeeTier::Override(fee_rate) => *fee_rate,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct CoinSplit {
+    pub gross: Coin,
+    /// The part of the donation the project receives.
+    pub net: Coin,
+    pub fee: Coin,
+    pub tier: FeeTier,
+    /// The part of the fee paid to the referrer, if the donation has one and the part is not zero.
+    pub referral: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct Payout {
+    pub recipient: Addr,
+    pub amount: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SimulateDonationResp {
+    pub coins: Vec<CoinSplit>,
+    /// The bank transfers the donation would make, except for the refund.
+    pub payouts: Vec<Payout>,
+    /// What would be sent back to the patron because it is not needed to cover the fee.
+    pub refund: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct ReferralEarningsResp {
+    pub earnings: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct StatsResp {
+    pub project_count: u128,
+    pub patron_count: u128,
+    pub denoms: Vec<DenomStats>,
+}
+
+#[cw_serde]
+pub struct GetRoundResp {
+    pub round: Round,
+    pub project_ids: Vec<u128>,
+}
+
+#[cw_serde]
+pub struct FrozenProject {
+    pub project_id: u128,
+    pub freeze: Freeze,
+}
+
+#[cw_serde]
+pub struct ListFrozenProjectsResp {
+    pub projects: Vec<FrozenProject>,
+}
+
+#[cw_serde]
+pub struct ModeratorsResp {
+    pub moderators: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct ProjectManager {
+    pub manager: Addr,
+    pub roles: Vec<ProjectRole>,
+}
+
+#[cw_serde]
+pub struct ProjectManagersResp {
+    pub managers: Vec<ProjectManager>,
+}
+
+#[cw_serde]
+pub struct OwnershipTransfer {
+    pub project_id: u128,
+    pub owner: Addr,
+    pub pending_owner: Addr,
+}
+
+#[cw_serde]
+pub struct PendingOwnershipTransfersResp {
+    pub transfers: Vec<OwnershipTransfer>,
+}
+
+/// The changes to the [`Config`]. The fields that are not set stay unchanged.
+#[cw_serde]
+#[derive(Default)]
+pub struct ConfigUpdate {
+    pub unique_project_names: Option<bool>,
+    /// A zero amount removes the fee.
+    pub project_creation_fee: Option<Coin>,
+    /// Zero removes the limit.
+    pub max_active_projects_per_creator: Option<u32>,
+    /// Pairs of address and weight. There must be at least one, all with a positive weight.
+    pub fee_collectors: Option<Vec<(String, u32)>>,
+    /// At most 1.
+    pub referral_share: Option<Decimal>,
+    /// `Some(None)`, sent as `null`, removes the guardian.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub guardian: Option<Option<String>>,
+}
+
+/// Tells a present `null` apart from a missing field, which `#[serde(default)]` turns into `None`.
+fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
-    CreateProject { name: String },
-    Donate { project_id: u128 },
+    CreateProject {
+        name: String,
+        /// At most 5 tags of at most 32 bytes each. They are stored lowercase.
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    Donate {
+        project_id: u128,
+        /// The address credited with the donation. Defaults to the sender.
+        on_behalf_of: Option<String>,
+        /// Anonymous donations only count towards the project totals and are not listed per patron.
+        #[serde(default)]
+        anonymous: bool,
+        /// A short non-empty message for the project, at most 256 bytes long.
+        memo: Option<String>,
+        /// The address that brought the patron, which gets a share of the fee.
+        referrer: Option<String>,
+        /// The amounts the project should receive in full, with the fee paid on top of them.
+        /// The funds must hold both and the rest of the funds is refunded.
+        cover_fee: Option<Vec<Coin>>,
+    },
+    /// Donates to several projects at once. The allocations must add up exactly to the attached funds.
+    DonateMany { allocations: Vec<(u128, Vec<Coin>)> },
+    /// Creates a quadratic funding round. The attached funds (exactly one coin) become the matching pool.
+    CreateRound {
+        name: String,
+        start: Timestamp,
+        end: Timestamp,
+    },
+    /// Invites the project into the round. Only the round operator can do this.
+    RegisterRoundProject { round_id: u128, project_id: u128 },
+    /// Accepts the invitation, so that donations to the project during the round window count towards
+    /// the round. A round takes at most `MAX_ROUND_PROJECTS` projects. Requires the `StatusManager` role.
+    JoinRound { round_id: u128, project_id: u128 },
+    /// Updates the fields that are set. Only the contract owner can do this.
+    UpdateConfig(ConfigUpdate),
+    /// Starts transferring the project to `new_owner`, replacing any earlier proposal.
+    /// Only the project creator can do this.
+    ProposeProjectOwner { project_id: u128, new_owner: String },
+    /// Completes the transfer, making the sender the project creator. Only the proposed owner can do this.
+    /// An active project counts towards the new owner's `max_active_projects_per_creator`.
+    /// The project managers and the beneficiary set by the previous owner are cleared.
+    AcceptProjectOwnership { project_id: u128 },
+    /// Replaces the roles of the manager. No roles removes the manager. Only the project creator can do this.
+    SetProjectManager {
+        project_id: u128,
+        manager: String,
+        roles: Vec<ProjectRole>,
+    },
+    /// Renames the project and/or replaces its tags. Requires the `MetadataEditor` role.
+    UpdateProjectMetadata {
+        project_id: u128,
+        name: Option<String>,
+        tags: Option<Vec<String>>,
+    },
+    /// Sends the project's payouts to `beneficiary`, or back to the creator if it is not set.
+    /// Requires the `BeneficiaryManager` role.
+    SetProjectBeneficiary {
+        project_id: u128,
+        beneficiary: Option<String>,
+    },
+    /// Requires the `StatusManager` role.
+    SetProjectStatus {
+        project_id: u128,
+        status: ProjectStatus,
+    },
+    /// Proposes `new_owner` as the contract owner, replacing any earlier proposal.
+    /// Only the contract owner can do this.
+    TransferOwnership {
+        new_owner: String,
+        /// When the proposal lapses. It never does if not set.
+        expiry: Option<Expiration>,
+    },
+    /// Completes the transfer, making the sender the contract owner. Only the proposed owner can do this.
+    AcceptOwnership {},
+    /// Leaves the contract without an owner for good, cancelling any pending transfer.
+    /// Only the contract owner can do this.
+    RenounceOwnership {},
+    /// Stops new projects and donations until `Unpause`. Only the contract owner and the guardian can do this.
+    Pause {},
+    /// Only the contract owner and the guardian can do this.
+    Unpause {},
+    /// Marks the project as verified, replacing any earlier verification. Only the contract owner can do this.
+    VerifyProject {
+        project_id: u128,
+        attestation_uri: Option<String>,
+    },
+    /// Only the contract owner can do this.
+    RevokeVerification { project_id: u128 },
+    /// Charges the project `fee_rate` (at most 1) instead of the global tiers, or the tiers again if not set.
+    /// Only the contract owner can do this.
+    SetFeeOverride {
+        project_id: u128,
+        fee_rate: Option<Decimal>,
+    },
+    /// Adds and removes moderators. Only the contract owner can do this.
+    UpdateModerators {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    /// Stops the project from receiving donations and hides it from `ListProjects`. Fails if the project
+    /// is frozen already. Only the contract owner and the moderators can do this.
+    FreezeProject { project_id: u128, reason: String },
+    /// Records the project's response to the freeze for the moderators to review.
+    /// Requires the `StatusManager` role.
+    AppealFreeze { project_id: u128, statement: String },
+    /// Only the contract owner and the moderators can do this.
+    UnfreezeProject { project_id: u128 },
+    /// Distributes the matching pool among the round's projects once the round has ended. Anyone can do this.
+    /// Projects that are frozen at that point get no matching.
+    FinalizeRound { round_id: u128 },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    /// Lists the projects, optionally filtered by a case-insensitive name prefix and a tag.
+    ///
+    /// The projects are ordered by normalized name when only `name_prefix` is given and by id otherwise.
+    /// `start_after` is the id of the last project on the previous page. Frozen projects are skipped
+    /// unless `include_frozen` is set, and unverified ones if `verified_only` is set.
     #[returns(ListProjectsResp)]
-    ListProjects {},
+    ListProjects {
+        name_prefix: Option<String>,
+        tag: Option<String>,
+        #[serde(default)]
+        include_frozen: bool,
+        #[serde(default)]
+        verified_only: bool,
+        start_after: Option<u128>,
+        limit: Option<u32>,
+    },
+    #[returns(ProjectResp)]
+    GetProject { project_id: u128 },
+    /// Fails if any of the projects does not exist.
+    #[returns(GetProjectsResp)]
+    GetProjects { ids: Vec<u128> },
+    /// Lists the projects of the creator in id order.
+    #[returns(ListProjectsByCreatorResp)]
+    ListProjectsByCreator {
+        creator: String,
+        start_after: Option<u128>,
+        limit: Option<u32>,
+    },
+    /// Looks the project up by the slug of its name. The slug is normalized, so the name itself works too.
+    #[returns(ProjectResp)]
+    GetProjectBySlug { slug: String },
+    #[returns(Config)]
+    Config {},
+    #[returns(Ownership)]
+    Ownership {},
     #[returns(ListDonationsForProjectByPatronResp)]
     ListDonationsForProjectByPatron { project_id: u128, patron: String },
+    #[returns(ProjectTotalsResp)]
+    ProjectTotals { project_id: u128 },
+    /// Lists the patrons who donated the most to the project in the given denom, biggest first.
+    #[returns(TopDonorsResp)]
+    TopDonors {
+        project_id: u128,
+        denom: String,
+        limit: Option<u32>,
+    },
+    /// Lists the projects that raised the most in the given denom, biggest first.
+    /// `start_after` is the id of the last project on the previous page. Frozen projects are skipped
+    /// unless `include_frozen` is set.
+    #[returns(TopProjectsResp)]
+    TopProjects {
+        denom: String,
+        #[serde(default)]
+        include_frozen: bool,
+        limit: Option<u32>,
+        start_after: Option<u128>,
+    },
+    /// Sums the donations to the project in the given denom per day or week, between `from` and `to` inclusive.
+    #[returns(DonationHistoryResp)]
+    DonationHistory {
+        project_id: u128,
+        denom: String,
+        from: Timestamp,
+        to: Timestamp,
+        granularity: Granularity,
+    },
+    /// Splits the funds exactly like `ExecuteMsg::Donate` would, without moving any tokens.
+    #[returns(SimulateDonationResp)]
+    SimulateDonation {
+        project_id: u128,
+        funds: Vec<Coin>,
+        /// The address that would donate, which cannot be the referrer.
+        sender: Option<String>,
+        /// The address credited with the donation, which cannot be the referrer either.
+        on_behalf_of: Option<String>,
+        #[serde(default)]
+        anonymous: bool,
+        referrer: Option<String>,
+        cover_fee: Option<Vec<Coin>>,
+    },
+    /// Returns what the referrer has earned from referrals so far, per denom.
+    #[returns(ReferralEarningsResp)]
+    ReferralEarnings { referrer: String },
+    /// Returns the contract-wide statistics since instantiation.
+    #[returns(StatsResp)]
+    Stats {},
+    #[returns(GetRoundResp)]
+    GetRound { round_id: u128 },
+    #[returns(ListFrozenProjectsResp)]
+    ListFrozenProjects {
+        start_after: Option<u128>,
+        limit: Option<u32>,
+    },
+    #[returns(ModeratorsResp)]
+    Moderators {},
+    #[returns(ProjectManagersResp)]
+    ProjectManagers { project_id: u128 },
+    /// Lists the ownership transfers that were proposed but not accepted yet, in project id order,
+    /// optionally only those to `pending_owner`.
+    #[returns(PendingOwnershipTransfersResp)]
+    PendingOwnershipTransfers {
+        pending_owner: Option<String>,
+        start_after: Option<u128>,
+        limit: Option<u32>,
+    },
 }
@@ -1,15 +1,115 @@
-use crate::error::{ContractResult, NonexistentProjectIdError};
-use crate::msg::{ListDonationsForProjectByPatronResp, ListProjectsResp, QueryMsg};
-use crate::state::{DONATIONS, PROJECTS, PROJECT_COUNT};
-use cosmwasm_std::{to_json_binary, Addr, Binary, Deps, Env};
+use crate::contract::SECONDS_PER_DAY;
+use crate::error::{
+    ContractError, ContractResult, NonexistentProjectIdError, NonexistentRoundIdError,
+};
+use crate::execute::{
+    payouts::Payouts, refunds, settle_donation, validate_patron, validate_referrer, DonateOptions,
+};
+use crate::msg::{
+    DonationHistoryResp, Donor, FrozenProject, GetProjectsResp, GetRoundResp, Granularity,
+    HistoryBucket, ListDonationsForProjectByPatronResp, ListFrozenProjectsResp,
+    ListProjectsByCreatorResp, ListProjectsResp, ModeratorsResp, OwnershipTransfer,
+    PendingOwnershipTransfersResp, ProjectManager, ProjectManagersResp, ProjectRaised, ProjectResp,
+    ProjectTotalsResp, QueryMsg, ReferralEarningsResp, SimulateDonationResp, StatsResp,
+    TopDonorsResp, TopProjectsResp,
+};
+use crate::state::{
+    name_index_key, normalize_name, slug_key, slugify, Project, CONFIG, DENOM_STATS, DONATIONS,
+    DONATION_BUCKETS, FEE_OVERRIDES, FROZEN_PROJECTS, MODERATORS, OWNERSHIP, PATRON_COUNT,
+    PENDING_PROJECT_OWNERS, PROJECTS, PROJECTS_BY_NAME, PROJECTS_BY_TAG, PROJECT_COUNT,
+    PROJECT_MANAGERS, PROJECT_SLUGS, PROJECT_TOTALS, REFERRAL_EARNINGS, ROUNDS, ROUND_PROJECTS,
+    TOP_DONORS, VERIFICATIONS,
+};
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Coin, Coins, Deps, Env, Order, StdResult, Timestamp,
+};
+use cw_storage_plus::Bound;
 
-fn list_projects(deps: &Deps) -> ContractResult<ListProjectsResp> {
-    let project_count = PROJECT_COUNT.load(deps.storage)?;
-    let mut projects = vec![];
-    for i in 0..project_count {
-        let project = PROJECTS.load(deps.storage, i)?;
-        projects.push(project);
-    }
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+fn list_projects(
+    deps: &Deps,
+    name_prefix: Option<String>,
+    tag: Option<String>,
+    include_frozen: bool,
+    verified_only: bool,
+    start_after: Option<u128>,
+    limit: Option<u32>,
+) -> ContractResult<ListProjectsResp> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let name_prefix = name_prefix.map(|prefix| normalize_name(&prefix));
+    let tag = tag.map(|tag| tag.trim().to_lowercase());
+    let is_listed = |id: u128| {
+        (include_frozen || !FROZEN_PROJECTS.has(deps.storage, id))
+            && (!verified_only || VERIFICATIONS.has(deps.storage, id))
+    };
+
+    let ids: Vec<u128> = match (&name_prefix, tag) {
+        (Some(name_prefix), None) => {
+            // Ranging over the name index only visits the matching names.
+            let min = match start_after {
+                Some(project_id) => {
+                    let project = PROJECTS
+                        .may_load(deps.storage, project_id)?
+                        .ok_or(NonexistentProjectIdError(project_id))?;
+                    let key = name_index_key(&normalize_name(&project.name), project_id);
+                    Bound::exclusive(key)
+                }
+                None => Bound::inclusive(name_prefix.as_bytes().to_vec()),
+            };
+            PROJECTS_BY_NAME
+                .keys_raw(deps.storage, Some(min), None, Order::Ascending)
+                .take_while(|key| key.starts_with(name_prefix.as_bytes()))
+                .map(|key| {
+                    let (_name, id) = key.split_at(key.len() - 16);
+                    u128::from_be_bytes(id.try_into().unwrap())
+                })
+                .filter(|id| is_listed(*id))
+                .take(limit)
+                .collect()
+        }
+        (name_prefix, Some(tag)) => {
+            let mut ids = vec![];
+            for id in PROJECTS_BY_TAG.prefix(&tag).keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            ) {
+                if ids.len() == limit {
+                    break;
+                }
+                let id = id?;
+                if !is_listed(id) {
+                    continue;
+                }
+                if let Some(name_prefix) = name_prefix {
+                    let project = PROJECTS.load(deps.storage, id)?;
+                    if !normalize_name(&project.name).starts_with(name_prefix) {
+                        continue;
+                    }
+                }
+                ids.push(id);
+            }
+            ids
+        }
+        (None, None) => PROJECTS
+            .keys(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .filter(|id| id.as_ref().map_or(true, |id| is_listed(*id)))
+            .take(limit)
+            .collect::<StdResult<_>>()?,
+    };
+
+    let projects = ids
+        .into_iter()
+        .map(|project_id| get_project(deps, project_id))
+        .collect::<ContractResult<_>>()?;
     let resp = ListProjectsResp { projects };
     Ok(resp)
 }
@@ -31,14 +131,466 @@ fn list_donations_for_project_by_patron(
     Ok(resp)
 }
 
+fn load_totals(deps: &Deps, project_id: u128) -> StdResult<Vec<Coin>> {
+    PROJECT_TOTALS
+        .prefix(project_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_denom, total)| total))
+        .collect()
+}
+
+fn project_resp(deps: &Deps, project_id: u128, project: Project) -> StdResult<ProjectResp> {
+    let totals = load_totals(deps, project_id)?;
+    let verification = VERIFICATIONS.may_load(deps.storage, project_id)?;
+    let fee_override = FEE_OVERRIDES.may_load(deps.storage, project_id)?;
+    let freeze = FROZEN_PROJECTS.may_load(deps.storage, project_id)?;
+    let resp = ProjectResp {
+        id: project_id,
+        project,
+        totals,
+        verification,
+        fee_override,
+        freeze,
+    };
+    Ok(resp)
+}
+
+fn get_project(deps: &Deps, project_id: u128) -> ContractResult<ProjectResp> {
+    let project = PROJECTS
+        .may_load(deps.storage, project_id)?
+        .ok_or(NonexistentProjectIdError(project_id))?;
+    let resp = project_resp(deps, project_id, project)?;
+    Ok(resp)
+}
+
+fn get_project_by_slug(deps: &Deps, slug: String) -> ContractResult<ProjectResp> {
+    let slug = slugify(&slug);
+    let project_id = PROJECT_SLUGS
+        .may_load(deps.storage, &slug_key(&slug))?
+        .ok_or(ContractError::NonexistentSlug(slug))?;
+    get_project(deps, project_id)
+}
+
+fn get_projects(deps: &Deps, ids: Vec<u128>) -> ContractResult<GetProjectsResp> {
+    let projects = ids
+        .into_iter()
+        .map(|project_id| get_project(deps, project_id))
+        .collect::<ContractResult<_>>()?;
+    let resp = GetProjectsResp { projects };
+    Ok(resp)
+}
+
+fn list_projects_by_creator(
+    deps: &Deps,
+    creator: String,
+    start_after: Option<u128>,
+    limit: Option<u32>,
+) -> ContractResult<ListProjectsByCreatorResp> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let projects = PROJECTS
+        .idx
+        .creator
+        .prefix(creator)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (id, project) = item?;
+            project_resp(deps, id, project)
+        })
+        .collect::<StdResult<_>>()?;
+    let resp = ListProjectsByCreatorResp { projects };
+    Ok(resp)
+}
+
+fn project_totals(deps: &Deps, project_id: u128) -> ContractResult<ProjectTotalsResp> {
+    let project_count = PROJECT_COUNT.load(deps.storage)?;
+    if project_id >= project_count {
+        return Err(NonexistentProjectIdError(project_id).into());
+    }
+    let totals = load_totals(deps, project_id)?;
+    let resp = ProjectTotalsResp { totals };
+    Ok(resp)
+}
+
+fn top_donors(
+    deps: &Deps,
+    project_id: u128,
+    denom: String,
+    limit: Option<u32>,
+) -> ContractResult<TopDonorsResp> {
+    let project_count = PROJECT_COUNT.load(deps.storage)?;
+    if project_id >= project_count {
+        return Err(NonexistentProjectIdError(project_id).into());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let donors = TOP_DONORS
+        .sub_prefix((project_id, denom.as_str()))
+        .keys(deps.storage, None, None, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(amount, patron)| Donor {
+                patron,
+                amount: amount.into(),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    let resp = TopDonorsResp { donors };
+    Ok(resp)
+}
+
+fn top_projects(
+    deps: &Deps,
+    denom: String,
+    include_frozen: bool,
+    limit: Option<u32>,
+    start_after: Option<u128>,
+) -> ContractResult<TopProjectsResp> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // The cursor is the (amount, primary key) pair of the last project on the previous page.
+    let max = match start_after {
+        Some(project_id) => {
+            let total = PROJECT_TOTALS
+                .may_load(deps.storage, (project_id, &denom))?
+                .ok_or(NonexistentProjectIdError(project_id))?;
+            Some(Bound::exclusive((
+                total.amount.u128(),
+                (project_id, denom.clone()),
+            )))
+        }
+        None => None,
+    };
+    let projects = PROJECT_TOTALS
+        .idx
+        .amount
+        .sub_prefix(denom)
+        .range(deps.storage, None, max, Order::Descending)
+        .map(|item| {
+            item.map(|((project_id, _denom), total)| ProjectRaised {
+                project_id,
+                amount: total.amount,
+            })
+        })
+        .filter(|item| {
+            item.as_ref().map_or(true, |raised| {
+                include_frozen || !FROZEN_PROJECTS.has(deps.storage, raised.project_id)
+            })
+        })
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    let resp = TopProjectsResp { projects };
+    Ok(resp)
+}
+
+fn donation_history(
+    deps: &Deps,
+    project_id: u128,
+    denom: String,
+    from: Timestamp,
+    to: Timestamp,
+    granularity: Granularity,
+) -> ContractResult<DonationHistoryResp> {
+    let project_count = PROJECT_COUNT.load(deps.storage)?;
+    if project_id >= project_count {
+        return Err(NonexistentProjectIdError(project_id).into());
+    }
+    let days_per_bucket = match granularity {
+        Granularity::Day => 1,
+        Granularity::Week => 7,
+    };
+    let from_day = from.seconds() / SECONDS_PER_DAY;
+    let to_day = to.seconds() / SECONDS_PER_DAY;
+
+    let mut buckets: Vec<HistoryBucket> = vec![];
+    for item in DONATION_BUCKETS.prefix((project_id, &denom)).range(
+        deps.storage,
+        Some(Bound::inclusive(from_day)),
+        Some(Bound::inclusive(to_day)),
+        Order::Ascending,
+    ) {
+        let (day_index, amount) = item?;
+        let start = Timestamp::from_seconds(
+            day_index / days_per_bucket * days_per_bucket * SECONDS_PER_DAY,
+        );
+        match buckets.last_mut() {
+            Some(bucket) if bucket.start == start => bucket.amount += amount,
+            _ => buckets.push(HistoryBucket { start, amount }),
+        }
+    }
+    let resp = DonationHistoryResp { buckets };
+    Ok(resp)
+}
+
+fn simulate_donation(
+    deps: &Deps,
+    project_id: u128,
+    funds: Vec<Coin>,
+    sender: Option<String>,
+    options: DonateOptions,
+) -> ContractResult<SimulateDonationResp> {
+    let DonateOptions {
+        on_behalf_of,
+        anonymous,
+        referrer,
+        cover_fee,
+        ..
+    } = options;
+    let project = PROJECTS
+        .may_load(deps.storage, project_id)?
+        .ok_or(NonexistentProjectIdError(project_id))?;
+    // The bank module hands the contract sorted funds without duplicates or zero amounts.
+    let funds = Coins::try_from(funds)?.into_vec();
+    let sender = sender
+        .map(|sender| deps.api.addr_validate(&sender))
+        .transpose()?;
+    let patron = validate_patron(deps.api, sender.as_ref(), on_behalf_of, anonymous)?;
+    let referrer = validate_referrer(deps.api, referrer, &[sender.as_ref(), patron.as_ref()])?;
+    let mut payouts = Payouts::default();
+    let coins = settle_donation(
+        deps.storage,
+        project_id,
+        &project,
+        &funds,
+        referrer.as_ref(),
+        cover_fee.as_deref(),
+        &mut payouts,
+    )?;
+    let resp = SimulateDonationResp {
+        refund: refunds(&funds, &coins),
+        coins,
+        payouts: payouts.into_vec(),
+    };
+    Ok(resp)
+}
+
+fn referral_earnings(deps: &Deps, referrer: String) -> ContractResult<ReferralEarningsResp> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    let earnings = REFERRAL_EARNINGS
+        .prefix(&referrer)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin::new(amount, denom)))
+        .collect::<StdResult<_>>()?;
+    let resp = ReferralEarningsResp { earnings };
+    Ok(resp)
+}
+
+fn stats(deps: &Deps) -> ContractResult<StatsResp> {
+    let project_count = PROJECT_COUNT.load(deps.storage)?;
+    let patron_count = PATRON_COUNT.load(deps.storage)?;
+    let denoms = DENOM_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_denom, stats)| stats))
+        .collect::<StdResult<_>>()?;
+    let resp = StatsResp {
+        project_count,
+        patron_count,
+        denoms,
+    };
+    Ok(resp)
+}
+
+fn get_round(deps: &Deps, round_id: u128) -> ContractResult<GetRoundResp> {
+    let round = ROUNDS
+        .may_load(deps.storage, round_id)?
+        .ok_or(NonexistentRoundIdError(round_id))?;
+    let project_ids = ROUND_PROJECTS
+        .prefix(round_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let resp = GetRoundResp { round, project_ids };
+    Ok(resp)
+}
+
+fn project_managers(deps: &Deps, project_id: u128) -> ContractResult<ProjectManagersResp> {
+    if !PROJECTS.has(deps.storage, project_id) {
+        return Err(NonexistentProjectIdError(project_id).into());
+    }
+    let managers = PROJECT_MANAGERS
+        .prefix(project_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(manager, roles)| ProjectManager { manager, roles }))
+        .collect::<StdResult<_>>()?;
+    let resp = ProjectManagersResp { managers };
+    Ok(resp)
+}
+
+fn moderators(deps: &Deps) -> ContractResult<ModeratorsResp> {
+    let moderators = MODERATORS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let resp = ModeratorsResp { moderators };
+    Ok(resp)
+}
+
+fn list_frozen_projects(
+    deps: &Deps,
+    start_after: Option<u128>,
+    limit: Option<u32>,
+) -> ContractResult<ListFrozenProjectsResp> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let projects = FROZEN_PROJECTS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(project_id, freeze)| FrozenProject { project_id, freeze }))
+        .collect::<StdResult<_>>()?;
+    let resp = ListFrozenProjectsResp { projects };
+    Ok(resp)
+}
+
+fn pending_ownership_transfers(
+    deps: &Deps,
+    pending_owner: Option<String>,
+    start_after: Option<u128>,
+    limit: Option<u32>,
+) -> ContractResult<PendingOwnershipTransfersResp> {
+    let pending_owner = pending_owner
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let transfers = PENDING_PROJECT_OWNERS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .filter(|item| match (item, &pending_owner) {
+            (Ok((_id, addr)), Some(pending_owner)) => addr == pending_owner,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| {
+            let (project_id, pending_owner) = item?;
+            let owner = PROJECTS.load(deps.storage, project_id)?.creator;
+            Ok(OwnershipTransfer {
+                project_id,
+                owner,
+                pending_owner,
+            })
+        })
+        .collect::<ContractResult<_>>()?;
+    let resp = PendingOwnershipTransfersResp { transfers };
+    Ok(resp)
+}
+
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
     use QueryMsg::*;
 
     let res: Binary = match msg {
-        ListProjects {} => to_json_binary(&list_projects(&deps)?)?,
+        ListProjects {
+            name_prefix,
+            tag,
+            include_frozen,
+            verified_only,
+            start_after,
+            limit,
+        } => to_json_binary(&list_projects(
+            &deps,
+            name_prefix,
+            tag,
+            include_frozen,
+            verified_only,
+            start_after,
+            limit,
+        )?)?,
+        GetProject { project_id } => to_json_binary(&get_project(&deps, project_id)?)?,
+        GetProjects { ids } => to_json_binary(&get_projects(&deps, ids)?)?,
+        ListProjectsByCreator {
+            creator,
+            start_after,
+            limit,
+        } => to_json_binary(&list_projects_by_creator(
+            &deps,
+            creator,
+            start_after,
+            limit,
+        )?)?,
+        GetProjectBySlug { slug } => to_json_binary(&get_project_by_slug(&deps, slug)?)?,
+        Config {} => to_json_binary(&CONFIG.load(deps.storage)?)?,
+        Ownership {} => to_json_binary(&OWNERSHIP.load(deps.storage)?)?,
         ListDonationsForProjectByPatron { project_id, patron } => to_json_binary(
             &list_donations_for_project_by_patron(&deps, project_id, patron)?,
         )?,
+        ProjectTotals { project_id } => to_json_binary(&project_totals(&deps, project_id)?)?,
+        TopDonors {
+            project_id,
+            denom,
+            limit,
+        } => to_json_binary(&top_donors(&deps, project_id, denom, limit)?)?,
+        TopProjects {
+            denom,
+            include_frozen,
+            limit,
+            start_after,
+        } => to_json_binary(&top_projects(
+            &deps,
+            denom,
+            include_frozen,
+            limit,
+            start_after,
+        )?)?,
+        DonationHistory {
+            project_id,
+            denom,
+            from,
+            to,
+            granularity,
+        } => to_json_binary(&donation_history(
+            &deps,
+            project_id,
+            denom,
+            from,
+            to,
+            granularity,
+        )?)?,
+        SimulateDonation {
+            project_id,
+            funds,
+            sender,
+            on_behalf_of,
+            anonymous,
+            referrer,
+            cover_fee,
+        } => {
+            let options = DonateOptions {
+                on_behalf_of,
+                anonymous,
+                referrer,
+                cover_fee,
+                ..Default::default()
+            };
+            to_json_binary(&simulate_donation(
+                &deps, project_id, funds, sender, options,
+            )?)?
+        }
+        ReferralEarnings { referrer } => to_json_binary(&referral_earnings(&deps, referrer)?)?,
+        Stats {} => to_json_binary(&stats(&deps)?)?,
+        GetRound { round_id } => to_json_binary(&get_round(&deps, round_id)?)?,
+        ListFrozenProjects { start_after, limit } => {
+            to_json_binary(&list_frozen_projects(&deps, start_after, limit)?)?
+        }
+        Moderators {} => to_json_binary(&moderators(&deps)?)?,
+        ProjectManagers { project_id } => to_json_binary(&project_managers(&deps, project_id)?)?,
+        PendingOwnershipTransfers {
+            pending_owner,
+            start_after,
+            limit,
+        } => to_json_binary(&pending_ownership_transfers(
+            &deps,
+            pending_owner,
+            start_after,
+            limit,
+        )?)?,
     };
 
     Ok(res)
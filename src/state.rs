@@ -1,31 +1,373 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
 
 #[cw_serde]
-pub struct DonationTx(pub Vec<Coin>);
+pub struct DonationTx {
+    pub funds: Vec<Coin>,
+    /// The message the patron left with the donation.
+    pub memo: Option<String>,
+}
+
+impl DonationTx {
+    pub fn new(funds: Vec<Coin>, memo: Option<String>) -> Self {
+        DonationTx { funds, memo }
+    }
+}
+
+#[cw_serde]
+pub enum ProjectStatus {
+    Active,
+    /// The project does not accept donations.
+    Inactive,
+}
+
+/// What a project manager is allowed to change. The creator can do all of it.
+#[cw_serde]
+#[derive(Copy)]
+pub enum ProjectRole {
+    MetadataEditor,
+    BeneficiaryManager,
+    StatusManager,
+}
 
 #[cw_serde]
 pub struct Project {
     pub name: String,
     pub creator: Addr,
+    pub status: ProjectStatus,
+    /// Lowercase tags for browsing, see `ListProjects`.
+    pub tags: Vec<String>,
+    /// The address that receives the donations instead of the creator.
+    pub beneficiary: Option<Addr>,
 }
 
 impl Project {
-    pub fn new(name: String, creator: Addr) -> Self {
-        Project { name, creator }
+    pub fn new(name: String, creator: Addr, tags: Vec<String>) -> Self {
+        Project {
+            name,
+            creator,
+            status: ProjectStatus::Active,
+            tags,
+            beneficiary: None,
+        }
+    }
+
+    /// The address the project's share of donations and matching is paid to.
+    pub fn recipient(&self) -> &Addr {
+        self.beneficiary.as_ref().unwrap_or(&self.creator)
     }
 }
 
+/// Normalizes the project name for case-insensitive prefix search:
+/// lowercases it, drops control characters and collapses whitespace.
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| !c.is_control())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Turns the project name into a slug: lowercase letters and digits separated by single dashes.
+///
+/// Names that only differ in case, punctuation or whitespace share the slug.
+pub fn slugify(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The key of the slug in PROJECT_SLUGS: the slug with lookalike characters folded into one,
+/// so that clones such as "WhaIes" (with a capital i) or "Pаypal" (with a Cyrillic а) collide
+/// with the original.
+pub fn slug_key(slug: &str) -> String {
+    slug.chars().map(fold_confusable).collect()
+}
+
+/// Maps the lowercase character to the ASCII character it is easily mistaken for.
+fn fold_confusable(c: char) -> char {
+    match c {
+        'i' | '1' | 'ı' | 'і' | 'ӏ' | 'ι' => 'l',
+        '0' | 'о' | 'ο' => 'o',
+        'а' | 'α' => 'a',
+        'с' => 'c',
+        'ԁ' => 'd',
+        'е' | 'ё' => 'e',
+        'һ' => 'h',
+        'ј' => 'j',
+        'κ' | 'к' => 'k',
+        'р' | 'ρ' => 'p',
+        'ԛ' => 'q',
+        'ѕ' => 's',
+        'υ' => 'u',
+        'ν' => 'v',
+        'ԝ' => 'w',
+        'х' | 'χ' => 'x',
+        'у' => 'y',
+        c => c,
+    }
+}
+
+/// The key of the project in PROJECTS_BY_NAME: the normalized name, a zero byte and the big-endian project id.
+pub fn name_index_key(normalized_name: &str, project_id: u128) -> Vec<u8> {
+    let mut key = normalized_name.as_bytes().to_vec();
+    key.push(0);
+    key.extend_from_slice(&project_id.to_be_bytes());
+    key
+}
+
 // TODO: consider coupling PROJECT_COUNT and PROJECTS into a single struct
 // We don't store an Item<Vec> because it is inefficient to load and store the entire list of projects every time we want to add a new project.
 // Source: https://book.cosmwasm.com/cross-contract/map-storage.html
 pub const PROJECT_COUNT: Item<u128> = Item::new("project_count");
-pub const PROJECTS: Map<u128, Project> = Map::new("projects");
+pub struct ProjectIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Project, u128>,
+}
+
+impl<'a> IndexList<Project> for ProjectIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Project>> + '_> {
+        let v: Vec<&dyn Index<Project>> = vec![&self.creator];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const PROJECTS: IndexedMap<u128, Project, ProjectIndexes> = IndexedMap::new(
+    "projects",
+    ProjectIndexes {
+        creator: MultiIndex::new(
+            |_pk, project| project.creator.clone(),
+            "projects",
+            "projects__creator",
+        ),
+    },
+);
+
+// The index of projects by normalized name, see `name_index_key`.
+// Composite keys length-prefix every component but the last, which would order the names by length first and
+// rule out prefix search, so the key is built by hand. Normalized names never contain the zero byte.
+pub const PROJECTS_BY_NAME: Map<Vec<u8>, ()> = Map::new("projects_by_name");
+
+#[cw_serde]
+pub struct Freeze {
+    /// Why a moderator froze the project.
+    pub reason: String,
+    pub frozen_by: Addr,
+    pub frozen_at: Timestamp,
+    /// The project's response to the freeze, if it appealed.
+    pub appeal: Option<String>,
+}
+
+// The projects frozen by a moderator. Frozen projects do not accept donations and are not listed by default.
+pub const FROZEN_PROJECTS: Map<u128, Freeze> = Map::new("frozen_projects");
+
+#[cw_serde]
+pub struct Verification {
+    pub verified_by: Addr,
+    pub verified_at: Timestamp,
+    /// Where the evidence behind the verification can be found.
+    pub attestation_uri: Option<String>,
+}
+
+// The projects verified by the contract owner.
+pub const VERIFICATIONS: Map<u128, Verification> = Map::new("verifications");
+
+// The map from a pair (referrer, denom) to the referrer's total share of the fees.
+pub const REFERRAL_EARNINGS: Map<(&Addr, &str), Uint128> = Map::new("referral_earnings");
+
+// The fee rates that replace the global tiers for some projects, set by the contract owner.
+pub const FEE_OVERRIDES: Map<u128, Decimal> = Map::new("fee_overrides");
+
+// The addresses that can freeze projects besides the contract owner.
+pub const MODERATORS: Map<&Addr, ()> = Map::new("moderators");
+
+// The roles the creator granted to each manager of the project.
+pub const PROJECT_MANAGERS: Map<(u128, &Addr), Vec<ProjectRole>> = Map::new("project_managers");
+
+// The address each project's ownership is being transferred to, until it accepts.
+pub const PENDING_PROJECT_OWNERS: Map<u128, Addr> = Map::new("pending_project_owners");
+
+// The map from a slug key to the project that claimed it first. See `slugify` and `slug_key`.
+pub const PROJECT_SLUGS: Map<&str, u128> = Map::new("project_slugs");
+
+// The index of projects by tag, keyed by (tag, project_id).
+pub const PROJECTS_BY_TAG: Map<(&str, u128), ()> = Map::new("projects_by_tag");
 
 // The map from a pair (project_id, patron) to a list of donations. The recorded donations are before the fees are deducted.
 // We don't use a newtype around u128 because it'd require implementing cw_storage_plus::PrimaryKey trait, which gets a bit verbose.
 pub const DONATIONS: Map<(u128, Addr), Vec<DonationTx>> = Map::new("donations");
 
-// Q: should we use a constant instead?
-pub const AUTHOR: Item<Addr> = Item::new("author");
+pub struct ProjectTotalIndexes<'a> {
+    // (denom, amount) -> (project_id, denom)
+    pub amount: MultiIndex<'a, (String, u128), Coin, (u128, String)>,
+}
+
+impl<'a> IndexList<Coin> for ProjectTotalIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Coin>> + '_> {
+        let v: Vec<&dyn Index<Coin>> = vec![&self.amount];
+        Box::new(v.into_iter())
+    }
+}
+
+// The map from a pair (project_id, denom) to the total amount donated to the project, before the fees are deducted.
+// Unlike DONATIONS, it includes anonymous donations.
+// The value is a Coin rather than a Uint128 because the index function only gets the raw primary key and needs the denom.
+pub const PROJECT_TOTALS: IndexedMap<(u128, &str), Coin, ProjectTotalIndexes> = IndexedMap::new(
+    "project_totals",
+    ProjectTotalIndexes {
+        amount: MultiIndex::new(
+            |_pk, total| (total.denom.clone(), total.amount.u128()),
+            "project_totals",
+            "project_totals__amount",
+        ),
+    },
+);
+
+// The map from a triple (project_id, denom, day_index) to the total amount donated to the project that day.
+// The day index is the number of whole days since the Unix epoch. Like PROJECT_TOTALS, it includes anonymous donations.
+pub const DONATION_BUCKETS: Map<(u128, &str, u64), Uint128> = Map::new("donation_buckets");
+
+// The map from a triple (project_id, denom, patron) to the total amount the patron has donated to the project.
+pub const PATRON_TOTALS: Map<(u128, &str, &Addr), Uint128> = Map::new("patron_totals");
+
+// The ordered index over PATRON_TOTALS, keyed by ((project_id, denom), total, patron).
+// Big-endian encoding of the total makes a descending range over the (project_id, denom) prefix a leaderboard.
+pub const TOP_DONORS: Map<((u128, &str), u128, &Addr), ()> = Map::new("top_donors");
+
+#[cw_serde]
+pub struct FeeCollector {
+    pub address: Addr,
+    pub weight: u32,
+}
+
+#[cw_serde]
+pub struct Config {
+    /// The addresses that share the donation and project creation fees in proportion to their weights.
+    /// The rounding remainder goes to the first one.
+    pub fee_collectors: Vec<FeeCollector>,
+    /// Whether `CreateProject` rejects names whose slug is already taken.
+    pub unique_project_names: bool,
+    /// The fee for `CreateProject`, sent to the fee collector.
+    pub project_creation_fee: Option<Coin>,
+    /// The maximum number of active projects a single address can create.
+    pub max_active_projects_per_creator: Option<u32>,
+    /// The share of the fee that goes to the referrer of a donation.
+    pub referral_share: Decimal,
+    /// The address that can pause the contract besides the contract owner.
+    pub guardian: Option<Addr>,
+    /// While paused, the contract rejects new projects and donations.
+    pub paused: bool,
+}
+
+impl Config {
+    pub fn new(fee_collector: Addr) -> Self {
+        Config {
+            fee_collectors: vec![FeeCollector {
+                address: fee_collector,
+                weight: 1,
+            }],
+            unique_project_names: true,
+            project_creation_fee: None,
+            max_active_projects_per_creator: None,
+            referral_share: Decimal::zero(),
+            guardian: None,
+            paused: false,
+        }
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// The contract owner and the ownership transfer in progress, if any.
+#[cw_serde]
+pub struct Ownership {
+    /// `None` once the ownership has been renounced.
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+    /// When the pending transfer can no longer be accepted.
+    pub pending_expiry: Option<Expiration>,
+}
+
+pub const OWNERSHIP: Item<Ownership> = Item::new("ownership");
+
+#[cw_serde]
+pub struct DenomStats {
+    pub denom: String,
+    /// The sum of the donations before the fees are deducted.
+    pub gross: Uint128,
+    pub fees: Uint128,
+    /// The sum paid out to the projects.
+    pub net: Uint128,
+}
+
+impl DenomStats {
+    pub fn new(denom: String) -> Self {
+        DenomStats {
+            denom,
+            gross: Uint128::zero(),
+            fees: Uint128::zero(),
+            net: Uint128::zero(),
+        }
+    }
+}
+
+// Contract-wide statistics. The number of projects is PROJECT_COUNT.
+// PATRONS is the set of addresses that have donated at least once. For anonymous donations, the sender is counted.
+pub const PATRON_COUNT: Item<u128> = Item::new("patron_count");
+pub const PATRONS: Map<&Addr, ()> = Map::new("patrons");
+pub const DENOM_STATS: Map<&str, DenomStats> = Map::new("denom_stats");
+
+#[cw_serde]
+pub struct Round {
+    pub name: String,
+    /// The address that funded the matching pool and registers projects into the round.
+    pub operator: Addr,
+    /// Only contributions in this denom are matched.
+    pub denom: String,
+    pub matching_pool: Uint128,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub finalized: bool,
+}
+
+impl Round {
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        !self.finalized && self.start <= now && now < self.end
+    }
+}
+
+pub const ROUND_COUNT: Item<u128> = Item::new("round_count");
+pub const ROUNDS: Map<u128, Round> = Map::new("rounds");
+
+// The pair of maps below represents the many-to-many relation between rounds and projects.
+// ROUND_PROJECTS is keyed by (round_id, project_id) and PROJECT_ROUNDS by (project_id, round_id)
+// so that both finalisation and `donate` can iterate over a prefix. PROJECT_ROUNDS entries are removed
+// once the round is over, so `donate` only visits the rounds that can still count.
+pub const ROUND_PROJECTS: Map<(u128, u128), ()> = Map::new("round_projects");
+pub const PROJECT_ROUNDS: Map<(u128, u128), ()> = Map::new("project_rounds");
+
+// The projects the round operator invited, keyed by (round_id, project_id). A project only enters
+// ROUND_PROJECTS and PROJECT_ROUNDS once it accepts, so nobody can tie it to rounds it did not choose.
+pub const ROUND_INVITATIONS: Map<(u128, u128), ()> = Map::new("round_invitations");
+
+// The map from a triple (round_id, project_id, patron) to the sum of the patron's donations
+// to the project made in the round's denom during the round window.
+pub const ROUND_CONTRIBUTIONS: Map<(u128, u128, Addr), Uint128> = Map::new("round_contributions");
+
+/// The running sums over a project's contributions in a round, so that finalizing does not have to
+/// read every contribution.
+#[cw_serde]
+#[derive(Default)]
+pub struct RoundTally {
+    pub sum: Decimal256,
+    pub sum_of_sqrts: Decimal256,
+}
+
+pub const ROUND_TALLIES: Map<(u128, u128), RoundTally> = Map::new("round_tallies");